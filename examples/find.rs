@@ -1,4 +1,5 @@
 use rustforce::{Client, Error, RestApi};
+use serde_json::Value;
 use std::env;
 
 #[tokio::main]
@@ -15,7 +16,9 @@ async fn main() -> Result<(), Error> {
 
     let mut api = RestApi::new(client);
 
-    let account = api.find_by_id("Account", "0011t00001FfE7iAAF").await?;
+    let account: Value = api
+        .find_by_id("Account", "0011t00001FfE7iAAF", Some(&["Id", "Name"]))
+        .await?;
     println!("{:?}", account);
 
     Ok(())
@@ -22,7 +22,7 @@ async fn main() -> Result<(), Error> {
     let res = api
         .upsert("Account", "ExKey__c", "0012K00001drfGYQAY1", params)
         .await?;
-    println!("{:?}", res.status());
+    println!("{:?}", res);
 
     Ok(())
 }
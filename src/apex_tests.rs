@@ -0,0 +1,354 @@
+use crate::responses::apex_test_response::{ApexTestResultRecord, RunTestsResult};
+use crate::xml::escape_xml;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Pass,
+    Fail,
+    CompileFail,
+    Skip,
+}
+
+impl TestOutcome {
+    fn parse(outcome: &str) -> TestOutcome {
+        match outcome {
+            "Pass" => TestOutcome::Pass,
+            "Skip" => TestOutcome::Skip,
+            "CompileFail" => TestOutcome::CompileFail,
+            // "Fail" and anything Salesforce adds later count as failures
+            _ => TestOutcome::Fail,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, TestOutcome::Fail | TestOutcome::CompileFail)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestMethodResult {
+    pub class_name: String,
+    pub method_name: String,
+    pub outcome: TestOutcome,
+    pub message: Option<String>,
+    pub stack_trace: Option<String>,
+    pub run_time_ms: u64,
+}
+
+impl From<ApexTestResultRecord> for TestMethodResult {
+    fn from(record: ApexTestResultRecord) -> Self {
+        TestMethodResult {
+            class_name: record.apex_class.map(|c| c.name).unwrap_or_default(),
+            method_name: record.method_name,
+            outcome: TestOutcome::parse(&record.outcome),
+            message: record.message,
+            stack_trace: record.stack_trace,
+            run_time_ms: record.run_time.unwrap_or(0),
+        }
+    }
+}
+
+/// Line coverage of one Apex class or trigger.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClassCoverage {
+    pub name: String,
+    pub lines_covered: u32,
+    pub lines_uncovered: u32,
+}
+
+impl ClassCoverage {
+    /// Covered lines as a percentage, 0.0 - 100.0. A class without lines counts as fully covered.
+    pub fn percent(&self) -> f64 {
+        let total = self.lines_covered + self.lines_uncovered;
+        if total == 0 {
+            100.0
+        } else {
+            self.lines_covered as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Outcome of an Apex test run, from either `ToolingApi::run_tests` or `run_tests_synchronous`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TestRunSummary {
+    /// `AsyncApexJob` Id; `None` for synchronous runs.
+    pub job_id: Option<String>,
+    pub tests: Vec<TestMethodResult>,
+    pub coverage: Vec<ClassCoverage>,
+}
+
+impl TestRunSummary {
+    pub fn passed(&self) -> usize {
+        self.count(|o| o == TestOutcome::Pass)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|o| o.is_failure())
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| o == TestOutcome::Skip)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &TestMethodResult> {
+        self.tests.iter().filter(|t| t.outcome.is_failure())
+    }
+
+    /// Overall line coverage of the classes in `coverage`, 0.0 - 100.0.
+    pub fn coverage_percent(&self) -> f64 {
+        let covered: u32 = self.coverage.iter().map(|c| c.lines_covered).sum();
+        let uncovered: u32 = self.coverage.iter().map(|c| c.lines_uncovered).sum();
+        ClassCoverage {
+            name: String::new(),
+            lines_covered: covered,
+            lines_uncovered: uncovered,
+        }
+        .percent()
+    }
+
+    /// Render the results as JUnit XML, one `<testsuite>` per test class.
+    pub fn to_junit_xml(&self) -> String {
+        let mut classes: Vec<&str> = vec![];
+        for test in &self.tests {
+            if !classes.contains(&test.class_name.as_str()) {
+                classes.push(&test.class_name);
+            }
+        }
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites name=\"Apex\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+            self.tests.len(),
+            self.failed(),
+            self.skipped(),
+            seconds(self.tests.iter().map(|t| t.run_time_ms).sum())
+        ));
+        for class in classes {
+            let tests: Vec<&TestMethodResult> = self
+                .tests
+                .iter()
+                .filter(|t| t.class_name == class)
+                .collect();
+            xml.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+                escape_xml(class),
+                tests.len(),
+                tests.iter().filter(|t| t.outcome.is_failure()).count(),
+                tests.iter().filter(|t| t.outcome == TestOutcome::Skip).count(),
+                seconds(tests.iter().map(|t| t.run_time_ms).sum())
+            ));
+            for test in tests {
+                xml.push_str(&format!(
+                    "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                    escape_xml(&test.method_name),
+                    escape_xml(class),
+                    seconds(test.run_time_ms)
+                ));
+                match test.outcome {
+                    TestOutcome::Pass => xml.push_str("/>\n"),
+                    TestOutcome::Skip => xml.push_str(">\n      <skipped/>\n    </testcase>\n"),
+                    TestOutcome::Fail | TestOutcome::CompileFail => {
+                        let message = test.message.as_deref().unwrap_or_default();
+                        let tag = if test.outcome == TestOutcome::Fail {
+                            "failure"
+                        } else {
+                            "error"
+                        };
+                        xml.push_str(&format!(
+                            ">\n      <{tag} message=\"{}\">{}</{tag}>\n    </testcase>\n",
+                            escape_xml(message),
+                            escape_xml(test.stack_trace.as_deref().unwrap_or(message)),
+                            tag = tag
+                        ));
+                    }
+                }
+            }
+            xml.push_str("  </testsuite>\n");
+        }
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+
+    fn count<F: Fn(TestOutcome) -> bool>(&self, predicate: F) -> usize {
+        self.tests.iter().filter(|t| predicate(t.outcome)).count()
+    }
+}
+
+impl From<RunTestsResult> for TestRunSummary {
+    fn from(result: RunTestsResult) -> Self {
+        let successes = result.successes.into_iter().map(|s| TestMethodResult {
+            class_name: s.name,
+            method_name: s.method_name,
+            outcome: TestOutcome::Pass,
+            message: None,
+            stack_trace: None,
+            run_time_ms: s.time as u64,
+        });
+        let failures = result.failures.into_iter().map(|f| TestMethodResult {
+            class_name: f.name,
+            method_name: f.method_name,
+            outcome: TestOutcome::Fail,
+            message: Some(f.message),
+            stack_trace: f.stack_trace,
+            run_time_ms: f.time as u64,
+        });
+        TestRunSummary {
+            job_id: None,
+            tests: successes.chain(failures).collect(),
+            coverage: result
+                .code_coverage
+                .into_iter()
+                .map(|c| ClassCoverage {
+                    name: c.name,
+                    lines_covered: c.num_locations.saturating_sub(c.num_locations_not_covered),
+                    lines_uncovered: c.num_locations_not_covered,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::apex_test_response::{
+        CodeCoverageResult, RunTestFailure, RunTestSuccess,
+    };
+
+    fn test(class: &str, method: &str, outcome: TestOutcome) -> TestMethodResult {
+        TestMethodResult {
+            class_name: class.to_string(),
+            method_name: method.to_string(),
+            outcome,
+            message: None,
+            stack_trace: None,
+            run_time_ms: 250,
+        }
+    }
+
+    fn summary() -> TestRunSummary {
+        let mut failure = test("AccountTest", "testBad", TestOutcome::Fail);
+        failure.message = Some("System.AssertException: Expected <1>".to_string());
+        failure.stack_trace = Some("Class.AccountTest.testBad: line 10, column 1".to_string());
+        TestRunSummary {
+            job_id: Some("707xx".to_string()),
+            tests: vec![
+                test("AccountTest", "testOk", TestOutcome::Pass),
+                failure,
+                test("LeadTest", "testSkip", TestOutcome::Skip),
+            ],
+            coverage: vec![
+                ClassCoverage {
+                    name: "AccountService".to_string(),
+                    lines_covered: 8,
+                    lines_uncovered: 2,
+                },
+                ClassCoverage {
+                    name: "LeadService".to_string(),
+                    lines_covered: 7,
+                    lines_uncovered: 3,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_counts() {
+        let summary = summary();
+        assert_eq!(summary.passed(), 1);
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(summary.skipped(), 1);
+        assert!(!summary.is_success());
+        assert_eq!(summary.failures().next().unwrap().method_name, "testBad");
+        assert_eq!(summary.coverage[0].percent(), 80.0);
+        assert_eq!(summary.coverage_percent(), 75.0);
+    }
+
+    #[test]
+    fn test_outcome_from_record() {
+        let record: ApexTestResultRecord = serde_json::from_value(serde_json::json!({
+            "ApexClass": {"Name": "AccountTest"},
+            "MethodName": "testOk",
+            "Outcome": "CompileFail",
+            "Message": "Compile error",
+            "RunTime": 12
+        }))
+        .unwrap();
+        let result = TestMethodResult::from(record);
+        assert_eq!(result.class_name, "AccountTest");
+        assert_eq!(result.outcome, TestOutcome::CompileFail);
+        assert!(result.outcome.is_failure());
+        assert_eq!(result.run_time_ms, 12);
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let xml = summary().to_junit_xml();
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().name(), "testsuites");
+        assert_eq!(root.attribute("tests"), Some("3"));
+        assert_eq!(root.attribute("failures"), Some("1"));
+        assert_eq!(root.attribute("time"), Some("0.750"));
+
+        let suites: Vec<_> = root.children().filter(|n| n.is_element()).collect();
+        assert_eq!(suites.len(), 2);
+        assert_eq!(suites[0].attribute("name"), Some("AccountTest"));
+        assert_eq!(suites[0].attribute("tests"), Some("2"));
+
+        let failure = doc
+            .descendants()
+            .find(|n| n.has_tag_name("failure"))
+            .unwrap();
+        assert_eq!(
+            failure.attribute("message"),
+            Some("System.AssertException: Expected <1>")
+        );
+        assert_eq!(
+            failure.text(),
+            Some("Class.AccountTest.testBad: line 10, column 1")
+        );
+        assert!(doc.descendants().any(|n| n.has_tag_name("skipped")));
+    }
+
+    #[test]
+    fn test_from_synchronous_result() {
+        let summary = TestRunSummary::from(RunTestsResult {
+            num_tests_run: 2,
+            num_failures: 1,
+            successes: vec![RunTestSuccess {
+                name: "AccountTest".to_string(),
+                method_name: "testOk".to_string(),
+                time: 40.0,
+                ..Default::default()
+            }],
+            failures: vec![RunTestFailure {
+                name: "AccountTest".to_string(),
+                method_name: "testBad".to_string(),
+                message: "boom".to_string(),
+                ..Default::default()
+            }],
+            code_coverage: vec![CodeCoverageResult {
+                name: "AccountService".to_string(),
+                num_locations: 10,
+                num_locations_not_covered: 4,
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        assert!(summary.job_id.is_none());
+        assert_eq!(summary.passed(), 1);
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(summary.tests[0].run_time_ms, 40);
+        assert_eq!(summary.coverage[0].lines_covered, 6);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// API request usage reported by the `Sforce-Limit-Info` response header.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_api_usage.htm>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiUsage {
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl ApiUsage {
+    /// Parse the `api-usage=used/limit` entry of a `Sforce-Limit-Info` header value.
+    pub fn parse(header: &str) -> Option<ApiUsage> {
+        header.split(',').find_map(|entry| {
+            let (name, value) = entry.trim().split_once('=')?;
+            if name != "api-usage" {
+                return None;
+            }
+            let (used, limit) = value.split_once('/')?;
+            Some(ApiUsage {
+                used: used.trim().parse().ok()?,
+                limit: limit.trim().parse().ok()?,
+            })
+        })
+    }
+
+    /// Usage as a percentage of the limit, 0.0 - 100.0.
+    pub fn percent(&self) -> f64 {
+        if self.limit == 0 {
+            0.0
+        } else {
+            self.used as f64 * 100.0 / self.limit as f64
+        }
+    }
+}
+
+type ApiUsageCallback = Arc<dyn Fn(&ApiUsage) + Send + Sync>;
+
+/// Logs a warning, and optionally calls back, when API usage rises past `percent`.
+#[derive(Clone)]
+pub struct ApiUsageThreshold {
+    percent: f64,
+    callback: Option<ApiUsageCallback>,
+}
+
+impl ApiUsageThreshold {
+    pub fn new(percent: f64) -> Self {
+        ApiUsageThreshold {
+            percent,
+            callback: None,
+        }
+    }
+
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ApiUsage) + Send + Sync + 'static,
+    {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn percent(&self) -> f64 {
+        self.percent
+    }
+
+    /// Fires only on the response that takes usage from below the threshold to at or above it.
+    pub(crate) fn check(&self, previous: Option<&ApiUsage>, current: &ApiUsage) {
+        let before = previous.map(|u| u.percent()).unwrap_or(0.0);
+        let now = current.percent();
+        if before < self.percent && now >= self.percent {
+            log::warn!(
+                "Salesforce API usage at {:.1}% ({}/{}), above the {}% threshold.",
+                now,
+                current.used,
+                current.limit,
+                self.percent
+            );
+            if let Some(callback) = &self.callback {
+                callback(current);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ApiUsageThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ApiUsageThreshold")
+            .field("percent", &self.percent)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            ApiUsage::parse("api-usage=123/15000"),
+            Some(ApiUsage {
+                used: 123,
+                limit: 15000
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_with_per_app_usage() {
+        let header = "per-app-api-usage=17/250(appName=sample-app), api-usage=25/5000";
+        assert_eq!(
+            ApiUsage::parse(header),
+            Some(ApiUsage {
+                used: 25,
+                limit: 5000
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(ApiUsage::parse(""), None);
+        assert_eq!(ApiUsage::parse("api-usage=abc/100"), None);
+        assert_eq!(ApiUsage::parse("api-usage=100"), None);
+    }
+
+    #[test]
+    fn test_percent() {
+        let usage = ApiUsage {
+            used: 1500,
+            limit: 15000,
+        };
+        assert_eq!(usage.percent(), 10.0);
+        assert_eq!(ApiUsage { used: 5, limit: 0 }.percent(), 0.0);
+    }
+
+    #[test]
+    fn test_threshold_fires_once_when_crossed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let threshold = ApiUsageThreshold::new(80.0).with_callback(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let low = ApiUsage {
+            used: 70,
+            limit: 100,
+        };
+        let high = ApiUsage {
+            used: 85,
+            limit: 100,
+        };
+        let higher = ApiUsage {
+            used: 90,
+            limit: 100,
+        };
+
+        threshold.check(None, &low);
+        threshold.check(Some(&low), &high);
+        threshold.check(Some(&high), &higher);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        threshold.check(None, &high);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_threshold_debug() {
+        let threshold = ApiUsageThreshold::new(90.0).with_callback(|_| {});
+        let debug = format!("{:?}", threshold);
+        assert!(debug.contains("90.0"));
+        assert!(debug.contains("callback: true"));
+    }
+}
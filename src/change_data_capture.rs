@@ -0,0 +1,402 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Record ID sent in overflow events, which cover every record of the entity.
+const WILDCARD_RECORD_ID: &str = "000000000000000AAA";
+
+/// Channel of an object's change events, e.g. `/data/AccountChangeEvent` or
+/// `/data/Invoice__ChangeEvent` for `Invoice__c`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.change_data_capture.meta/change_data_capture/cdc_subscribe_channels.htm>
+pub fn change_event_channel(sobject_name: &str) -> String {
+    match sobject_name.strip_suffix("__c") {
+        Some(custom) => format!("/data/{}__ChangeEvent", custom),
+        None => format!("/data/{}ChangeEvent", sobject_name),
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.change_data_capture.meta/change_data_capture/cdc_event_fields_header.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeType {
+    Create,
+    Update,
+    Delete,
+    Undelete,
+    GapCreate,
+    GapUpdate,
+    GapDelete,
+    GapUndelete,
+    /// More changes in one transaction than Salesforce sends events for.
+    GapOverflow,
+    /// Any change type without a dedicated variant, as sent.
+    Other(String),
+}
+
+impl ChangeType {
+    pub fn parse(value: &str) -> ChangeType {
+        match value {
+            "CREATE" => ChangeType::Create,
+            "UPDATE" => ChangeType::Update,
+            "DELETE" => ChangeType::Delete,
+            "UNDELETE" => ChangeType::Undelete,
+            "GAP_CREATE" => ChangeType::GapCreate,
+            "GAP_UPDATE" => ChangeType::GapUpdate,
+            "GAP_DELETE" => ChangeType::GapDelete,
+            "GAP_UNDELETE" => ChangeType::GapUndelete,
+            "GAP_OVERFLOW" => ChangeType::GapOverflow,
+            other => ChangeType::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ChangeType::Create => "CREATE",
+            ChangeType::Update => "UPDATE",
+            ChangeType::Delete => "DELETE",
+            ChangeType::Undelete => "UNDELETE",
+            ChangeType::GapCreate => "GAP_CREATE",
+            ChangeType::GapUpdate => "GAP_UPDATE",
+            ChangeType::GapDelete => "GAP_DELETE",
+            ChangeType::GapUndelete => "GAP_UNDELETE",
+            ChangeType::GapOverflow => "GAP_OVERFLOW",
+            ChangeType::Other(other) => other,
+        }
+    }
+
+    /// Gap events carry no field values; the records have to be read again.
+    pub fn is_gap(&self) -> bool {
+        matches!(
+            self,
+            ChangeType::GapCreate
+                | ChangeType::GapUpdate
+                | ChangeType::GapDelete
+                | ChangeType::GapUndelete
+                | ChangeType::GapOverflow
+        )
+    }
+}
+
+impl Serialize for ChangeType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ChangeType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(ChangeType::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEventHeader {
+    pub entity_name: String,
+    #[serde(default)]
+    pub record_ids: Vec<String>,
+    pub change_type: ChangeType,
+    #[serde(default)]
+    pub change_origin: String,
+    /// Shared by every event of one transaction.
+    pub transaction_key: String,
+    /// Order of the event within its transaction, starting at 1.
+    pub sequence_number: u64,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub commit_timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub commit_number: u64,
+    #[serde(default)]
+    pub commit_user: String,
+    /// Fields set by an update, e.g. `Name` or `BillingAddress.City` for compound fields.
+    #[serde(default)]
+    pub changed_fields: Vec<String>,
+    #[serde(default)]
+    pub nulled_fields: Vec<String>,
+    /// Large text fields whose value in the event is a unified diff rather than the new text.
+    #[serde(default)]
+    pub diff_fields: Vec<String>,
+}
+
+/// Payload of a change event, for use as `StreamEvent<ChangeEvent>`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.change_data_capture.meta/change_data_capture/cdc_message_structure.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    #[serde(rename = "ChangeEventHeader")]
+    pub header: ChangeEventHeader,
+    /// Field values: every field of created and undeleted records, the changed ones of updates.
+    #[serde(flatten)]
+    pub fields: Map<String, Value>,
+}
+
+/// What applying a change event to a record map left to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeOutcome {
+    Applied,
+    /// A gap event, or an update that cannot be applied to the map; read these records again.
+    Reload(Vec<String>),
+    /// An overflow event; read every record of this entity again.
+    ReloadAll(String),
+}
+
+impl ChangeEvent {
+    pub fn is_gap(&self) -> bool {
+        self.header.change_type.is_gap()
+    }
+
+    pub fn is_overflow(&self) -> bool {
+        self.header.change_type == ChangeType::GapOverflow
+            || self
+                .header
+                .record_ids
+                .iter()
+                .any(|id| id == WILDCARD_RECORD_ID)
+    }
+
+    /// Apply the event to records keyed by ID. Creates and undeletes insert the record, updates
+    /// merge the changed fields into it and deletes remove it. Gap and overflow events leave the
+    /// map untouched and report which records to read again, as do updates that carry diffs
+    /// and updates of records missing from the map.
+    pub fn apply_to(&self, records: &mut HashMap<String, Map<String, Value>>) -> ChangeOutcome {
+        if self.is_overflow() {
+            return ChangeOutcome::ReloadAll(self.header.entity_name.clone());
+        }
+        if self.is_gap() {
+            return ChangeOutcome::Reload(self.header.record_ids.clone());
+        }
+        if self.header.change_type == ChangeType::Update && !self.header.diff_fields.is_empty() {
+            return ChangeOutcome::Reload(self.header.record_ids.clone());
+        }
+
+        let mut missing = vec![];
+        for id in &self.header.record_ids {
+            match self.header.change_type {
+                ChangeType::Create | ChangeType::Undelete => {
+                    let mut record = self.fields.clone();
+                    record.insert("Id".to_string(), Value::String(id.clone()));
+                    records.insert(id.clone(), record);
+                }
+                // An update only holds the changed fields, too little to cache a record from
+                ChangeType::Update => match records.get_mut(id) {
+                    Some(record) => self.merge_into(record),
+                    None => missing.push(id.clone()),
+                },
+                ChangeType::Delete => {
+                    records.remove(id);
+                }
+                _ => {}
+            }
+        }
+        if missing.is_empty() {
+            ChangeOutcome::Applied
+        } else {
+            ChangeOutcome::Reload(missing)
+        }
+    }
+
+    fn merge_into(&self, record: &mut Map<String, Value>) {
+        for (name, value) in &self.fields {
+            match (record.get_mut(name), value) {
+                // Compound fields such as addresses only hold their changed components
+                (Some(Value::Object(existing)), Value::Object(changed)) => {
+                    for (component, value) in changed {
+                        existing.insert(component.clone(), value.clone());
+                    }
+                }
+                _ => {
+                    record.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        for name in &self.header.nulled_fields {
+            match name.split_once('.') {
+                Some((compound, component)) => {
+                    if let Some(Value::Object(existing)) = record.get_mut(compound) {
+                        existing.insert(component.to_string(), Value::Null);
+                    }
+                }
+                None => {
+                    record.insert(name.clone(), Value::Null);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn change_event(change_type: &str, record_ids: Value, fields: Value) -> ChangeEvent {
+        let mut payload = json!({
+            "ChangeEventHeader": {
+                "entityName": "Account",
+                "recordIds": record_ids,
+                "changeType": change_type,
+                "changeOrigin": "com/salesforce/api/rest/60.0",
+                "transactionKey": "0002343d-9d90-e395-ed20-cf416ba652ad",
+                "sequenceNumber": 1,
+                "commitTimestamp": 1704110400000i64,
+                "commitNumber": 10585193272713u64,
+                "commitUser": "005xx",
+                "changedFields": []
+            }
+        });
+        for (name, value) in fields.as_object().unwrap() {
+            payload[name] = value.clone();
+        }
+        serde_json::from_value(payload).unwrap()
+    }
+
+    #[test]
+    fn test_change_event_channel() {
+        assert_eq!(change_event_channel("Account"), "/data/AccountChangeEvent");
+        assert_eq!(
+            change_event_channel("Invoice__c"),
+            "/data/Invoice__ChangeEvent"
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let event = change_event(
+            "UPDATE",
+            json!(["001xx"]),
+            json!({"Name": "Acme", "LastModifiedDate": "2024-01-01T12:00:00.000Z"}),
+        );
+        assert_eq!(event.header.change_type, ChangeType::Update);
+        assert_eq!(
+            event.header.commit_timestamp,
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+        );
+        assert_eq!(event.header.commit_number, 10585193272713);
+        assert_eq!(event.fields["Name"], "Acme");
+        assert!(!event.fields.contains_key("ChangeEventHeader"));
+        assert!(!event.is_gap());
+    }
+
+    #[test]
+    fn test_change_type() {
+        assert!(ChangeType::parse("GAP_UPDATE").is_gap());
+        assert!(!ChangeType::parse("UNDELETE").is_gap());
+        assert_eq!(
+            ChangeType::parse("SOMETHING_NEW"),
+            ChangeType::Other("SOMETHING_NEW".to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(ChangeType::GapOverflow).unwrap(),
+            "GAP_OVERFLOW"
+        );
+    }
+
+    #[test]
+    fn test_apply_create_update_delete_undelete() {
+        let mut records = HashMap::new();
+
+        let create = change_event(
+            "CREATE",
+            json!(["001xx"]),
+            json!({
+                "Name": "Acme",
+                "Phone": "555-0100",
+                "BillingAddress": {"City": "Berlin", "Country": "DE"}
+            }),
+        );
+        assert_eq!(create.apply_to(&mut records), ChangeOutcome::Applied);
+        assert_eq!(records["001xx"]["Id"], "001xx");
+
+        let mut update = change_event(
+            "UPDATE",
+            json!(["001xx"]),
+            json!({"Name": "Acme Corp", "BillingAddress": {"City": "Hamburg"}}),
+        );
+        update.header.nulled_fields = vec!["Phone".to_string()];
+        update.apply_to(&mut records);
+        let record = &records["001xx"];
+        assert_eq!(record["Name"], "Acme Corp");
+        assert_eq!(record["Phone"], Value::Null);
+        assert_eq!(
+            record["BillingAddress"],
+            json!({"City": "Hamburg", "Country": "DE"})
+        );
+
+        let delete = change_event("DELETE", json!(["001xx"]), json!({}));
+        delete.apply_to(&mut records);
+        assert!(records.is_empty());
+
+        let undelete = change_event("UNDELETE", json!(["001xx"]), json!({"Name": "Acme Corp"}));
+        undelete.apply_to(&mut records);
+        assert_eq!(records["001xx"]["Name"], "Acme Corp");
+    }
+
+    #[test]
+    fn test_apply_update_to_several_records() {
+        let mut records = HashMap::new();
+        for id in ["001a", "001b"] {
+            change_event("CREATE", json!([id]), json!({"Rating": "Cold"})).apply_to(&mut records);
+        }
+        let update = change_event("UPDATE", json!(["001a", "001b"]), json!({"Rating": "Hot"}));
+        assert_eq!(update.apply_to(&mut records), ChangeOutcome::Applied);
+        assert_eq!(records["001a"]["Rating"], "Hot");
+        assert_eq!(records["001b"]["Rating"], "Hot");
+    }
+
+    #[test]
+    fn test_apply_update_to_unknown_record() {
+        let mut records = HashMap::new();
+        change_event("CREATE", json!(["001a"]), json!({"Rating": "Cold"})).apply_to(&mut records);
+
+        let update = change_event("UPDATE", json!(["001a", "001b"]), json!({"Rating": "Hot"}));
+        assert_eq!(
+            update.apply_to(&mut records),
+            ChangeOutcome::Reload(vec!["001b".to_string()])
+        );
+        assert_eq!(records["001a"]["Rating"], "Hot");
+        assert!(!records.contains_key("001b"));
+    }
+
+    #[test]
+    fn test_apply_update_with_diff_fields() {
+        let mut records = HashMap::new();
+        change_event(
+            "CREATE",
+            json!(["001a"]),
+            json!({"Description": "first draft"}),
+        )
+        .apply_to(&mut records);
+
+        let mut update = change_event(
+            "UPDATE",
+            json!(["001a"]),
+            json!({"Description": "@@ -1,11 +1,12 @@\n-first draft\n+second draft"}),
+        );
+        update.header.diff_fields = vec!["Description".to_string()];
+        assert_eq!(
+            update.apply_to(&mut records),
+            ChangeOutcome::Reload(vec!["001a".to_string()])
+        );
+        assert_eq!(records["001a"]["Description"], "first draft");
+    }
+
+    #[test]
+    fn test_apply_gap_and_overflow() {
+        let mut records = HashMap::new();
+        let gap = change_event("GAP_UPDATE", json!(["001a", "001b"]), json!({}));
+        assert_eq!(
+            gap.apply_to(&mut records),
+            ChangeOutcome::Reload(vec!["001a".to_string(), "001b".to_string()])
+        );
+
+        let overflow = change_event("GAP_OVERFLOW", json!([WILDCARD_RECORD_ID]), json!({}));
+        assert!(overflow.is_overflow());
+        assert_eq!(
+            overflow.apply_to(&mut records),
+            ChangeOutcome::ReloadAll("Account".to_string())
+        );
+        assert!(records.is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::client::client::Client;
+use crate::errors::Error;
+use crate::responses::error_response::ErrorResponse;
+use reqwest::{Method, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Typed calls to custom Apex REST endpoints under `/services/apexrest`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.apexcode.meta/apexcode/apex_rest.htm>
+#[derive(Default)]
+pub struct ApexRestApi {
+    pub(crate) client: Client,
+    namespace: Option<String>,
+}
+
+impl ApexRestApi {
+    pub fn new(client: Client) -> Self {
+        ApexRestApi {
+            client,
+            namespace: None,
+        }
+    }
+
+    /// Prefix every path with the namespace of a managed package, e.g. `/services/apexrest/acme/...`.
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        let namespace = namespace.trim_matches('/');
+        self.namespace = if namespace.is_empty() {
+            None
+        } else {
+            Some(namespace.to_string())
+        };
+        self
+    }
+
+    /// `{instance_url}/services/apexrest[/{namespace}]/{path}`
+    pub(crate) fn endpoint_url(&self, path: &str) -> Result<String, Error> {
+        let instance_url = self
+            .client
+            .instance_url
+            .as_ref()
+            .ok_or(Error::NotLoggedIn)?;
+        let path = path.trim_start_matches('/');
+        Ok(match &self.namespace {
+            Some(namespace) => format!("{}/services/apexrest/{}/{}", instance_url, namespace, path),
+            None => format!("{}/services/apexrest/{}", instance_url, path),
+        })
+    }
+
+    pub async fn get<Res: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Res, Error> {
+        self.send(Method::GET, path, params, None::<&()>).await
+    }
+
+    pub async fn post<Req: Serialize, Res: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        body: &Req,
+    ) -> Result<Res, Error> {
+        self.send(Method::POST, path, &[], Some(body)).await
+    }
+
+    pub async fn patch<Req: Serialize, Res: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        body: &Req,
+    ) -> Result<Res, Error> {
+        self.send(Method::PATCH, path, &[], Some(body)).await
+    }
+
+    pub async fn put<Req: Serialize, Res: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        body: &Req,
+    ) -> Result<Res, Error> {
+        self.send(Method::PUT, path, &[], Some(body)).await
+    }
+
+    pub async fn delete<Res: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Res, Error> {
+        self.send(Method::DELETE, path, params, None::<&()>).await
+    }
+
+    async fn send<Req: Serialize, Res: DeserializeOwned>(
+        &mut self,
+        method: Method,
+        path: &str,
+        params: &[(&str, &str)],
+        body: Option<&Req>,
+    ) -> Result<Res, Error> {
+        let url = self.endpoint_url(path)?;
+        let params: Vec<(String, String)> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let res = self.client.send_json(method, url, &params, body).await?;
+        handle_apex_response(res).await
+    }
+}
+
+/// An empty body deserializes as `null`, so `()` and `Option<T>` work for endpoints without output.
+async fn handle_apex_response<T: DeserializeOwned>(res: Response) -> Result<T, Error> {
+    let status = res.status();
+    let body = res.text().await?;
+    if status.is_success() {
+        let body = if body.trim().is_empty() {
+            "null"
+        } else {
+            &body
+        };
+        return Ok(serde_json::from_str(body)?);
+    }
+    if let Ok(errors) = serde_json::from_str::<Vec<ErrorResponse>>(&body) {
+        return Err(Error::ErrorResponses(errors));
+    }
+    if let Ok(error) = serde_json::from_str::<ErrorResponse>(&body) {
+        return Err(Error::ErrorResponses(vec![error]));
+    }
+    Err(Error::ErrorResponses(vec![ErrorResponse {
+        message: if body.is_empty() {
+            status.to_string()
+        } else {
+            body
+        },
+        error_code: status.as_u16().to_string(),
+        fields: None,
+    }]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    fn create_test_apex_rest_api(server_url: &str) -> ApexRestApi {
+        let mut client = Client::new();
+        client.set_instance_url(server_url);
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        client.set_version("v60.0");
+        ApexRestApi::new(client)
+    }
+
+    #[derive(Serialize)]
+    struct OrderRequest {
+        sku: String,
+        quantity: u32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct OrderResponse {
+        order_id: String,
+    }
+
+    #[test]
+    fn test_endpoint_url() {
+        let api = create_test_apex_rest_api("https://example.my.salesforce.com");
+        assert_eq!(
+            api.endpoint_url("/orders/1").unwrap(),
+            "https://example.my.salesforce.com/services/apexrest/orders/1"
+        );
+        let api = api.with_namespace("/acme/");
+        assert_eq!(
+            api.endpoint_url("orders").unwrap(),
+            "https://example.my.salesforce.com/services/apexrest/acme/orders"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_url_not_logged_in() {
+        let api = ApexRestApi::new(Client::new());
+        assert!(matches!(
+            api.endpoint_url("orders"),
+            Err(Error::NotLoggedIn)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_keeps_query_order_and_duplicates() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/apexrest/acme/orders")
+            .match_query(Matcher::Exact("status=open&tag=a&tag=b".into()))
+            .match_header("authorization", "Bearer test_token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!([{"orderId": "801A"}]).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url()).with_namespace("acme");
+        let orders: Vec<OrderResponse> = api
+            .get("orders", &[("status", "open"), ("tag", "a"), ("tag", "b")])
+            .await
+            .unwrap();
+        assert_eq!(orders[0].order_id, "801A");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_post() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/apexrest/orders")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(json!({"sku": "W-1", "quantity": 2})))
+            .with_status(201)
+            .with_body(json!({"orderId": "801B"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url());
+        let order: OrderResponse = api
+            .post(
+                "/orders",
+                &OrderRequest {
+                    sku: "W-1".to_string(),
+                    quantity: 2,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(order.order_id, "801B");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_patch_and_put() {
+        let mut server = Server::new_async().await;
+        let patch = server
+            .mock("PATCH", "/services/apexrest/orders/801B")
+            .match_body(Matcher::Json(json!({"quantity": 3})))
+            .with_status(200)
+            .with_body(json!({"orderId": "801B"}).to_string())
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/services/apexrest/orders/801B")
+            .match_body(Matcher::Json(json!({"sku": "W-2", "quantity": 1})))
+            .with_status(200)
+            .with_body(json!({"orderId": "801B"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url());
+        let patched: Value = api
+            .patch("orders/801B", &json!({"quantity": 3}))
+            .await
+            .unwrap();
+        assert_eq!(patched["orderId"], "801B");
+        let replaced: OrderResponse = api
+            .put(
+                "orders/801B",
+                &OrderRequest {
+                    sku: "W-2".to_string(),
+                    quantity: 1,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(replaced.order_id, "801B");
+        patch.assert_async().await;
+        put.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_empty_body() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("DELETE", "/services/apexrest/orders/801B")
+            .match_query(Matcher::UrlEncoded("reason".into(), "duplicate".into()))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url());
+        let res: () = api
+            .delete("orders/801B", &[("reason", "duplicate")])
+            .await
+            .unwrap();
+        assert_eq!(res, ());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_error_list() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/services/apexrest/orders/missing")
+            .with_status(500)
+            .with_body(
+                json!([{"errorCode": "APEX_ERROR", "message": "System.QueryException: List has no rows"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url());
+        let err = api.get::<Value>("orders/missing", &[]).await.unwrap_err();
+        match err {
+            Error::ErrorResponses(errors) => assert_eq!(errors[0].error_code, "APEX_ERROR"),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_single_object_and_plain_text() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/services/apexrest/orders")
+            .with_status(400)
+            .with_body(json!({"errorCode": "INVALID_SKU", "message": "Unknown SKU"}).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", "/services/apexrest/orders")
+            .with_status(405)
+            .with_body("Method not allowed")
+            .create_async()
+            .await;
+
+        let mut api = create_test_apex_rest_api(&server.url());
+        match api.post::<_, Value>("orders", &json!({})).await {
+            Err(Error::ErrorResponses(errors)) => {
+                assert_eq!(errors[0].error_code, "INVALID_SKU");
+                assert_eq!(errors[0].message, "Unknown SKU");
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+        match api.delete::<Value>("orders", &[]).await {
+            Err(Error::ErrorResponses(errors)) => {
+                assert_eq!(errors[0].error_code, "405");
+                assert_eq!(errors[0].message, "Method not allowed");
+            }
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use crate::access_token::AccessToken;
+use crate::api_usage::{ApiUsage, ApiUsageThreshold};
 use crate::errors::Error;
+use crate::requests::request_options::RequestOptions;
 use crate::responses::error_response::ErrorResponse;
 use crate::responses::token_response::TokenResponse;
 use crate::xml::{extract_xml_tag, create_login_envelope};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
-use reqwest::{Response, Url};
+use reqwest::multipart::Form;
+use reqwest::{Method, RequestBuilder, Response, Url};
 use serde::Serialize;
-use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Clone, Debug)]
@@ -18,8 +20,12 @@ pub struct Client {
     pub(crate) instance_url: Option<String>,
     pub(crate) access_token: Option<AccessToken>,
     pub(crate) refresh_token: Option<String>,
+    pub(crate) org_id: Option<String>,
     pub(crate) version: String,
     pub(crate) secret_required: bool,
+    pub(crate) api_usage: Option<ApiUsage>,
+    pub(crate) api_usage_thresholds: Vec<ApiUsageThreshold>,
+    pub(crate) request_options: RequestOptions,
 }
 
 impl Default for Client {
@@ -39,8 +45,12 @@ impl Client {
             access_token: None,
             instance_url: None,
             refresh_token: None,
+            org_id: None,
             secret_required: true,
             version: "v60.0".to_string(),
+            api_usage: None,
+            api_usage_thresholds: vec![],
+            request_options: RequestOptions::default(),
         }
     }
 
@@ -78,6 +88,20 @@ impl Client {
         self.refresh_token.as_deref()
     }
 
+    /// Organization ID of the logged in user, captured during login.
+    pub fn org_id(&self) -> Option<&str> {
+        self.org_id.as_deref()
+    }
+
+    /// API usage from the `Sforce-Limit-Info` header of the most recent response.
+    pub fn api_usage(&self) -> Option<ApiUsage> {
+        self.api_usage
+    }
+
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.request_options
+    }
+
     pub fn base_path(&self) -> Result<String, Error> {
         let instance_url = self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?;
         Ok(format!("{}/services/data/{}", instance_url, self.version))
@@ -120,6 +144,25 @@ impl Client {
         self
     }
 
+    pub fn set_org_id(&mut self, org_id: &str) -> &mut Self {
+        self.org_id = Some(org_id.to_string());
+        self
+    }
+
+    /// Warn (and call back, if configured) whenever API usage crosses `threshold`.
+    pub fn add_api_usage_threshold(&mut self, threshold: ApiUsageThreshold) -> &mut Self {
+        self.api_usage_thresholds.push(threshold);
+        self
+    }
+
+    /// Salesforce headers added to every request; headers passed to a call take precedence.
+    /// Invalid options are rejected and the previous ones kept.
+    pub fn set_request_options(&mut self, options: RequestOptions) -> Result<&mut Self, Error> {
+        options.validate()?;
+        self.request_options = options;
+        Ok(self)
+    }
+
     /// Set Access token if you've already obtained one via one of the OAuth2 flows
     pub fn set_access_token(
         &mut self,
@@ -218,6 +261,9 @@ impl Client {
         }
 
         let response: TokenResponse = res.json().await?;
+        if let Some(org_id) = org_id_from_identity_url(&response.id) {
+            self.org_id = Some(org_id);
+        }
         let token_type = response.token_type.unwrap_or_default();
         self.set_access_token(response.access_token, response.issued_at, token_type);
         self.instance_url = Some(response.instance_url);
@@ -259,6 +305,9 @@ impl Client {
         }
 
         let response: TokenResponse = res.json().await?;
+        if let Some(org_id) = org_id_from_identity_url(&response.id) {
+            self.org_id = Some(org_id);
+        }
         let token_type = response.token_type.unwrap_or_default();
         self.set_access_token(response.access_token, response.issued_at, token_type);
         self.instance_url = Some(response.instance_url);
@@ -296,6 +345,7 @@ impl Client {
                 None => None,
             };
             self.instance_url = extract_xml_tag("serverUrl", body_response.as_str());
+            self.org_id = extract_xml_tag("organizationId", body_response.as_str());
             Ok(self)
         } else {
             let body_response = res.text().await?;
@@ -320,12 +370,8 @@ impl Client {
         );
         let parsed = Url::parse(&resource_url)
             .map_err(|e| Error::ConfigError(format!("Invalid URL: {}", e)))?;
-        // Some ownership absurdity for string refs accessed through iterators with collect
-        let hash_query: HashMap<_, _> = parsed.query_pairs().into_owned().collect();
-        let params_string: Vec<(String, String)> = hash_query
-            .keys()
-            .map(|k| (String::from(k), String::from(&hash_query[k])))
-            .collect();
+        // Keep the pairs in order, including repeated keys
+        let params_string: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
         let params: Vec<(&str, &str)> = params_string
             .iter()
             .map(|(x, y)| (&x[..], &y[..]))
@@ -346,14 +392,12 @@ impl Client {
             self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?,
             path
         );
-        let res = self
+        let request = self
             .http_client
             .get(url.as_str())
             .headers(self.create_header(vec![])?)
-            .query(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .query(&params);
+        self.send(request).await
     }
 
     pub async fn rest_post<T: Serialize>(
@@ -368,14 +412,12 @@ impl Client {
             self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?,
             path
         );
-        let res = self
+        let request = self
             .http_client
             .post(url)
             .headers(self.create_header(vec![])?)
-            .json(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .json(&params);
+        self.send(request).await
     }
 
     pub async fn rest_patch<T: Serialize>(
@@ -390,14 +432,12 @@ impl Client {
             self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?,
             path
         );
-        let res = self
+        let request = self
             .http_client
             .patch(url.as_str())
             .headers(self.create_header(vec![])?)
-            .json(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .json(&params);
+        self.send(request).await
     }
 
     pub async fn rest_put<T: Serialize>(
@@ -412,14 +452,12 @@ impl Client {
             self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?,
             path
         );
-        let res = self
+        let request = self
             .http_client
             .put(url.as_str())
             .headers(self.create_header(vec![])?)
-            .json(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .json(&params);
+        self.send(request).await
     }
 
     pub async fn rest_delete(&mut self, path: String) -> Result<Response, Error> {
@@ -430,30 +468,35 @@ impl Client {
             self.instance_url.as_ref().ok_or(Error::NotLoggedIn)?,
             path
         );
-        let res = self
+        let request = self
             .http_client
             .delete(url.as_str())
-            .headers(self.create_header(vec![])?)
-            .send()
-            .await?;
-        Ok(res)
+            .headers(self.create_header(vec![])?);
+        self.send(request).await
     }
 
     pub async fn get(
         &mut self,
         url: String,
         params: Vec<(String, String)>,
+    ) -> Result<Response, Error> {
+        self.get_with_headers(url, params, vec![]).await
+    }
+
+    pub async fn get_with_headers(
+        &mut self,
+        url: String,
+        params: Vec<(String, String)>,
+        headers: Vec<(String, String)>,
     ) -> Result<Response, Error> {
         self.ensure_refresh().await?;
 
-        let res = self
+        let request = self
             .http_client
             .get(url.as_str())
-            .headers(self.create_header(vec![])?)
-            .query(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .headers(self.create_header(headers)?)
+            .query(&params);
+        self.send(request).await
     }
 
     pub async fn get_raw(
@@ -465,8 +508,8 @@ impl Client {
 
         let mut headers = self.create_header(additional_headers)?;
         headers.remove("Accept");
-        let res = self.http_client.get(url).headers(headers).send().await?;
-        Ok(res)
+        let request = self.http_client.get(url).headers(headers);
+        self.send(request).await
     }
 
     pub async fn post<T: Serialize>(
@@ -477,14 +520,12 @@ impl Client {
     ) -> Result<Response, Error> {
         self.ensure_refresh().await?;
 
-        let res = self
+        let request = self
             .http_client
             .post(url)
             .headers(self.create_header(headers)?)
-            .json(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .json(&params);
+        self.send(request).await
     }
 
     pub async fn post_raw_buffer(
@@ -495,14 +536,29 @@ impl Client {
     ) -> Result<Response, Error> {
         self.ensure_refresh().await?;
 
-        let res = self
+        let request = self
             .http_client
             .post(url)
             .headers(self.create_header(headers)?)
-            .body(body)
-            .send()
-            .await?;
-        Ok(res)
+            .body(body);
+        self.send(request).await
+    }
+
+    /// POST a `multipart/form-data` body. Parts built from streams are sent without buffering.
+    pub async fn post_multipart(
+        &mut self,
+        url: String,
+        form: Form,
+        headers: Vec<(String, String)>,
+    ) -> Result<Response, Error> {
+        self.ensure_refresh().await?;
+
+        let request = self
+            .http_client
+            .post(url)
+            .headers(self.create_header(headers)?)
+            .multipart(form);
+        self.send(request).await
     }
 
     pub async fn put(&mut self, url: String, buffer: Vec<u8>) -> Result<Response, Error> {
@@ -511,38 +567,70 @@ impl Client {
         let mut headers = self.create_header(vec![])?;
         headers.insert("Content-Type", HeaderValue::from_static("text/csv"));
         headers.insert("Accept", HeaderValue::from_static("application/json"));
-        let res = self
+        let request = self
             .http_client
             .put(url.as_str())
             .headers(headers)
-            .body(buffer)
-            .send()
-            .await?;
-        Ok(res)
+            .body(buffer);
+        self.send(request).await
     }
 
     pub async fn patch<T: Serialize>(&mut self, url: String, params: T) -> Result<Response, Error> {
         self.ensure_refresh().await?;
 
-        let res = self
+        let request = self
             .http_client
             .patch(url.as_str())
             .headers(self.create_header(vec![])?)
-            .json(&params)
-            .send()
-            .await?;
-        Ok(res)
+            .json(&params);
+        self.send(request).await
+    }
+
+    /// Send a request with query parameters and an optional JSON body.
+    pub(crate) async fn send_json<T: Serialize>(
+        &mut self,
+        method: Method,
+        url: String,
+        params: &[(String, String)],
+        body: Option<&T>,
+    ) -> Result<Response, Error> {
+        self.ensure_refresh().await?;
+
+        let mut request = self
+            .http_client
+            .request(method, url.as_str())
+            .headers(self.create_header(vec![])?)
+            .query(params);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        self.send(request).await
     }
 
     pub async fn delete(&mut self, url: String) -> Result<Response, Error> {
         self.ensure_refresh().await?;
 
-        let res = self
+        let request = self
             .http_client
             .delete(url.as_str())
-            .headers(self.create_header(vec![])?)
-            .send()
-            .await?;
+            .headers(self.create_header(vec![])?);
+        self.send(request).await
+    }
+
+    /// Every authenticated request goes through here so API usage is tracked on each response.
+    async fn send(&mut self, request: RequestBuilder) -> Result<Response, Error> {
+        let res = request.send().await?;
+        let usage = res
+            .headers()
+            .get("Sforce-Limit-Info")
+            .and_then(|v| v.to_str().ok())
+            .and_then(ApiUsage::parse);
+        if let Some(usage) = usage {
+            for threshold in &self.api_usage_thresholds {
+                threshold.check(self.api_usage.as_ref(), &usage);
+            }
+            self.api_usage = Some(usage);
+        }
         Ok(res)
     }
 
@@ -557,7 +645,8 @@ impl Client {
         //Default header
         headers.insert("Accept", HeaderValue::from_static("application/json"));
 
-        for (key, value) in additional_headers {
+        let option_headers = self.request_options.to_headers();
+        for (key, value) in option_headers.into_iter().chain(additional_headers) {
             let header_name: HeaderName = key
                 .parse()
                 .map_err(|_| Error::ConfigError(format!("Invalid header name: {}", key)))?;
@@ -574,6 +663,17 @@ impl Client {
     }
 }
 
+/// The identity URL has the form `https://login.salesforce.com/id/{org_id}/{user_id}`.
+fn org_id_from_identity_url(identity_url: &str) -> Option<String> {
+    let mut segments = identity_url.rsplit('/');
+    let _user_id = segments.next()?;
+    let org_id = segments.next().filter(|s| !s.is_empty())?;
+    match segments.next() {
+        Some("id") => Some(org_id.to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -604,7 +704,11 @@ mod tests {
         assert!(client.access_token.is_none());
         assert!(client.instance_url.is_none());
         assert!(client.refresh_token.is_none());
+        assert!(client.org_id.is_none());
         assert!(client.secret_required);
+        assert!(client.api_usage().is_none());
+        assert!(client.api_usage_thresholds.is_empty());
+        assert!(client.request_options().is_empty());
     }
 
     #[test]
@@ -673,6 +777,29 @@ mod tests {
         assert_eq!(Some("my_secret".to_string()), client.client_secret);
     }
 
+    #[test]
+    fn test_set_org_id() {
+        let mut client = Client::new();
+        assert_eq!(None, client.org_id());
+        client.set_org_id("00Dxx0000001gPL");
+        assert_eq!(Some("00Dxx0000001gPL"), client.org_id());
+    }
+
+    #[test]
+    fn test_org_id_from_identity_url() {
+        assert_eq!(
+            org_id_from_identity_url(
+                "https://login.salesforce.com/id/00Dxx0000001gPL/005xx000001Sv6e"
+            ),
+            Some("00Dxx0000001gPL".to_string())
+        );
+        assert_eq!(org_id_from_identity_url("id"), None);
+        assert_eq!(
+            org_id_from_identity_url("https://example.com/other/00D/005"),
+            None
+        );
+    }
+
     #[test]
     fn test_set_access_token() {
         let mut client = Client::new();
@@ -816,6 +943,55 @@ mod tests {
         assert_eq!(headers.get("Accept").unwrap(), "text/xml");
     }
 
+    #[test]
+    fn test_create_header_with_request_options() {
+        use crate::requests::request_options::{AutoAssign, MruUpdate};
+
+        let mut client = Client::new();
+        client.set_access_token("mytoken".to_string(), "".to_string(), "Bearer".to_string());
+        client
+            .set_request_options(
+                RequestOptions::new()
+                    .with_auto_assign(AutoAssign::None)
+                    .with_mru(MruUpdate::Skip),
+            )
+            .unwrap();
+
+        let headers = client
+            .create_header(vec![(
+                "Sforce-Mru".to_string(),
+                "updateMru=true".to_string(),
+            )])
+            .unwrap();
+
+        assert_eq!(headers.get("Sforce-Auto-Assign").unwrap(), "FALSE");
+        // Headers passed to the call win over the defaults
+        assert_eq!(headers.get("Sforce-Mru").unwrap(), "updateMru=true");
+    }
+
+    #[test]
+    fn test_set_invalid_request_options() {
+        let mut client = Client::new();
+        client.set_access_token("mytoken".to_string(), "".to_string(), "Bearer".to_string());
+        client
+            .set_request_options(RequestOptions::new().with_call_options_client("my-app"))
+            .unwrap();
+
+        let invalid = RequestOptions {
+            query_batch_size: Some(10),
+            ..Default::default()
+        };
+        match client.set_request_options(invalid) {
+            Err(Error::ConfigError(_)) => {}
+            other => panic!("Expected ConfigError, got {:?}", other.map(|_| ())),
+        }
+
+        // The previous options stay in effect and requests keep working
+        let headers = client.create_header(vec![]).unwrap();
+        assert_eq!(headers.get("Sforce-Call-Options").unwrap(), "client=my-app");
+        assert!(headers.get("Sforce-Query-Options").is_none());
+    }
+
     // --- ensure_refresh ---
 
     #[tokio::test]
@@ -874,6 +1050,7 @@ mod tests {
 
         assert!(result.is_ok());
         assert_eq!(client.access_token_value(), Some("PowerLevel9000"));
+        assert_eq!(client.org_id(), Some("00Dxx"));
         assert_eq!(client.instance_url.unwrap(), server.url());
         mock.assert_async().await;
     }
@@ -996,6 +1173,9 @@ mod tests {
                             <sessionId>soap_token_123</sessionId>
                             <serverUrl>https://na1.salesforce.com/services/Soap/u/60.0/00Dxx</serverUrl>
                             <serverTimestamp>2024-01-01T00:00:00.000Z</serverTimestamp>
+                            <userInfo>
+                                <organizationId>00Dxx0000001gPL</organizationId>
+                            </userInfo>
                         </result>
                     </loginResponse>
                 </soapenv:Body>
@@ -1017,6 +1197,7 @@ mod tests {
             .await;
 
         assert!(result.is_ok());
+        assert_eq!(client.org_id(), Some("00Dxx0000001gPL"));
         let token = client.access_token.unwrap();
         assert_eq!(token.value, "soap_token_123");
         assert_eq!(token.token_type, "Bearer");
@@ -1082,6 +1263,111 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_api_usage_tracked_from_limit_info_header() {
+        let mut server = Server::new_async().await;
+        let get = server
+            .mock("GET", "/test")
+            .with_status(200)
+            .with_header("Sforce-Limit-Info", "api-usage=123/15000")
+            .create_async()
+            .await;
+        let patch = server
+            .mock("PATCH", "/test")
+            .with_status(204)
+            .with_header("Sforce-Limit-Info", "api-usage=124/15000")
+            .create_async()
+            .await;
+
+        let mut client = create_test_client(&server.url());
+        client
+            .get(format!("{}/test", server.url()), vec![])
+            .await
+            .unwrap();
+        assert_eq!(
+            client.api_usage(),
+            Some(ApiUsage {
+                used: 123,
+                limit: 15000
+            })
+        );
+        client
+            .patch(format!("{}/test", server.url()), json!({}))
+            .await
+            .unwrap();
+        assert_eq!(client.api_usage().unwrap().used, 124);
+        get.assert_async().await;
+        patch.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_api_usage_threshold_callback() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let mut server = Server::new_async().await;
+        let below = server
+            .mock("GET", "/below")
+            .with_status(200)
+            .with_header("Sforce-Limit-Info", "api-usage=70/100")
+            .create_async()
+            .await;
+        let above = server
+            .mock("POST", "/above")
+            .with_status(200)
+            .with_header("Sforce-Limit-Info", "api-usage=91/100")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let seen = Arc::new(AtomicU64::new(0));
+        let seen_in_callback = seen.clone();
+        let mut client = create_test_client(&server.url());
+        client.add_api_usage_threshold(ApiUsageThreshold::new(90.0).with_callback(move |usage| {
+            seen_in_callback.fetch_add(usage.used, Ordering::SeqCst);
+        }));
+
+        client
+            .get(format!("{}/below", server.url()), vec![])
+            .await
+            .unwrap();
+        assert_eq!(seen.load(Ordering::SeqCst), 0);
+        for _ in 0..2 {
+            client
+                .post(format!("{}/above", server.url()), json!({}), vec![])
+                .await
+                .unwrap();
+        }
+        // Only the request that crossed the threshold triggers the callback
+        assert_eq!(seen.load(Ordering::SeqCst), 91);
+        below.assert_async().await;
+        above.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_with_headers() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/test")
+            .match_header("If-Modified-Since", "Mon, 01 Jan 2024 00:00:00 GMT")
+            .match_header("Accept", "application/json")
+            .with_status(304)
+            .create_async()
+            .await;
+
+        let mut client = create_test_client(&server.url());
+        let headers = vec![(
+            "If-Modified-Since".to_string(),
+            "Mon, 01 Jan 2024 00:00:00 GMT".to_string(),
+        )];
+        let res = client
+            .get_with_headers(format!("{}/test", server.url()), vec![], headers)
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 304);
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_post() {
         let mut server = Server::new_async().await;
@@ -1178,6 +1464,36 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_post_multipart() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/test")
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+            )
+            .match_body(mockito::Matcher::Regex(
+                r#"(?s)name="meta".*\{"a":1\}.*name="data"; filename="f.bin".*raw data"#
+                    .to_string(),
+            ))
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let mut client = create_test_client(&server.url());
+        let form = Form::new().text("meta", r#"{"a":1}"#).part(
+            "data",
+            reqwest::multipart::Part::bytes(b"raw data".to_vec()).file_name("f.bin"),
+        );
+        let res = client
+            .post_multipart(format!("{}/test", server.url()), form, vec![])
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 201);
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_get_raw() {
         let mut server = Server::new_async().await;
@@ -1350,6 +1666,23 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_rest_get_fulluri_keeps_repeated_params() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/apexrest/MyEndpoint")
+            .match_query(mockito::Matcher::Exact("b=2&a=1&a=3".into()))
+            .with_status(200)
+            .with_body("ok")
+            .create_async()
+            .await;
+
+        let mut client = create_test_client(&server.url());
+        let res = client.rest_get_fulluri("MyEndpoint?b=2&a=1&a=3").await;
+        assert!(res.is_ok());
+        mock.assert_async().await;
+    }
+
     // --- ensure_refresh with expired token ---
 
     #[tokio::test]
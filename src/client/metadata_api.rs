@@ -0,0 +1,1034 @@
+use crate::client::client::Client;
+use crate::client::soap_api::{first_result, parse_response, response_results, soap_call};
+use crate::errors::Error;
+use crate::requests::deploy_options::DeployOptions;
+use crate::requests::list_metadata_query::ListMetadataQuery;
+use crate::requests::retrieve_request::RetrieveRequest;
+use crate::responses::metadata_result::{
+    AsyncResult, DeployResult, FileProperties, MetadataSaveResult, RetrieveResult,
+};
+use crate::xml::{children_named, node_to_value, XmlWriter};
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+const METADATA_NAMESPACE: &str = "http://soap.sforce.com/2006/04/metadata";
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Most CRUD calls accept at most 10 components per request.
+const CRUD_CHUNK_SIZE: usize = 10;
+const LIST_QUERY_CHUNK_SIZE: usize = 3;
+
+/// SOAP Metadata API: zip deploys and retrieves, and synchronous CRUD calls on components.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_intro.htm>
+#[derive(Default)]
+pub struct MetadataApi {
+    pub(crate) client: Client,
+}
+
+impl MetadataApi {
+    pub fn new(client: Client) -> Self {
+        MetadataApi { client }
+    }
+
+    /// `{instance_url}/services/Soap/m/{version}`, with the version number lacking the `v` prefix.
+    pub(crate) fn soap_url(&self) -> Result<String, Error> {
+        let instance_url = self
+            .client
+            .instance_url
+            .as_ref()
+            .ok_or(Error::NotLoggedIn)?;
+        Ok(format!(
+            "{}/services/Soap/m/{}",
+            instance_url,
+            self.client.version.trim_start_matches('v')
+        ))
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_deploy.htm>
+    pub async fn deploy(
+        &mut self,
+        zip_file: &[u8],
+        options: &DeployOptions,
+    ) -> Result<AsyncResult, Error> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(zip_file);
+        let body = self
+            .call("deploy", |writer| {
+                writer.element("ZipFile", &encoded);
+                options.write_xml(writer);
+            })
+            .await?;
+        let doc = parse_response(&body)?;
+        Ok(AsyncResult::from_xml(first_result(&doc)?))
+    }
+
+    /// Component failures and test results are only included when `include_details` is set.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_checkdeploystatus.htm>
+    pub async fn check_deploy_status(
+        &mut self,
+        id: &str,
+        include_details: bool,
+    ) -> Result<DeployResult, Error> {
+        let body = self
+            .call("checkDeployStatus", |writer| {
+                writer.element("asyncProcessId", id);
+                writer.element("includeDetails", &include_details.to_string());
+            })
+            .await?;
+        let doc = parse_response(&body)?;
+        Ok(DeployResult::from_xml(first_result(&doc)?))
+    }
+
+    /// Poll a deployment until it is done, then return its result with details.
+    ///
+    /// Returns `Error::TimeoutError` when the deployment is not done after `max_wait`.
+    pub async fn wait_for_deploy(
+        &mut self,
+        id: &str,
+        poll_interval: Duration,
+        max_wait: Duration,
+    ) -> Result<DeployResult, Error> {
+        let deadline = Instant::now() + max_wait;
+        while !self.check_deploy_status(id, false).await?.done {
+            check_deadline("deployment", id, deadline, poll_interval, max_wait)?;
+            tokio::time::sleep(poll_interval).await;
+        }
+        self.check_deploy_status(id, true).await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_retrieve.htm>
+    pub async fn retrieve(&mut self, request: &RetrieveRequest) -> Result<AsyncResult, Error> {
+        let body = self
+            .call("retrieve", |writer| request.write_xml(writer))
+            .await?;
+        let doc = parse_response(&body)?;
+        Ok(AsyncResult::from_xml(first_result(&doc)?))
+    }
+
+    /// Salesforce discards the zip once it has been returned with `include_zip` set.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_checkretrievestatus.htm>
+    pub async fn check_retrieve_status(
+        &mut self,
+        id: &str,
+        include_zip: bool,
+    ) -> Result<RetrieveResult, Error> {
+        let body = self
+            .call("checkRetrieveStatus", |writer| {
+                writer.element("asyncProcessId", id);
+                writer.element("includeZip", &include_zip.to_string());
+            })
+            .await?;
+        let doc = parse_response(&body)?;
+        RetrieveResult::from_xml(first_result(&doc)?)
+            .map_err(|e| Error::XmlError(format!("invalid zipFile: {}", e)))
+    }
+
+    /// Poll a retrieve until it is done, then return its result with the zip.
+    ///
+    /// Returns `Error::TimeoutError` when the retrieve is not done after `max_wait`.
+    pub async fn wait_for_retrieve(
+        &mut self,
+        id: &str,
+        poll_interval: Duration,
+        max_wait: Duration,
+    ) -> Result<RetrieveResult, Error> {
+        let deadline = Instant::now() + max_wait;
+        while !self.check_retrieve_status(id, false).await?.done {
+            check_deadline("retrieve", id, deadline, poll_interval, max_wait)?;
+            tokio::time::sleep(poll_interval).await;
+        }
+        self.check_retrieve_status(id, true).await
+    }
+
+    /// Properties of every component matching the queries; folder-based types need a folder.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_listmetadata.htm>
+    pub async fn list_metadata(
+        &mut self,
+        queries: &[ListMetadataQuery],
+        as_of_version: Option<&str>,
+    ) -> Result<Vec<FileProperties>, Error> {
+        let mut properties = vec![];
+        for chunk in queries.chunks(LIST_QUERY_CHUNK_SIZE) {
+            let body = self
+                .call("listMetadata", |writer| {
+                    for query in chunk {
+                        query.write_xml(writer);
+                    }
+                    if let Some(version) = as_of_version {
+                        writer.element("asOfVersion", version);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            properties.extend(
+                response_results(&doc)
+                    .into_iter()
+                    .map(FileProperties::from_xml),
+            );
+        }
+        Ok(properties)
+    }
+
+    /// Components as JSON, with text values as strings and repeated elements as arrays.
+    /// Names that do not exist are left out.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_readMetadata.htm>
+    pub async fn read_metadata(
+        &mut self,
+        type_name: &str,
+        full_names: &[&str],
+    ) -> Result<Vec<Value>, Error> {
+        let mut records = vec![];
+        for chunk in full_names.chunks(CRUD_CHUNK_SIZE) {
+            let body = self
+                .call("readMetadata", |writer| {
+                    writer.element("type", type_name);
+                    for full_name in chunk {
+                        writer.element("fullNames", full_name);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            for result in response_results(&doc) {
+                records.extend(
+                    children_named(result, "records")
+                        .map(node_to_value)
+                        .filter(|record| record.get("fullName").is_some()),
+                );
+            }
+        }
+        Ok(records)
+    }
+
+    /// Components are serialized field by field, e.g. `{"fullName": "Account.Tier__c", "label": "Tier", ...}`.
+    ///
+    /// Sent 10 components per call, so the save is not atomic: when a later call fails, the
+    /// earlier components stay saved and their results come back in
+    /// `Error::MetadataPartialFailure`. The same applies to update, upsert and delete.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_createMetadata.htm>
+    pub async fn create_metadata<T: Serialize>(
+        &mut self,
+        type_name: &str,
+        components: &[T],
+    ) -> Result<Vec<MetadataSaveResult>, Error> {
+        self.save_metadata("createMetadata", type_name, components)
+            .await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_updateMetadata.htm>
+    pub async fn update_metadata<T: Serialize>(
+        &mut self,
+        type_name: &str,
+        components: &[T],
+    ) -> Result<Vec<MetadataSaveResult>, Error> {
+        self.save_metadata("updateMetadata", type_name, components)
+            .await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_upsertMetadata.htm>
+    pub async fn upsert_metadata<T: Serialize>(
+        &mut self,
+        type_name: &str,
+        components: &[T],
+    ) -> Result<Vec<MetadataSaveResult>, Error> {
+        self.save_metadata("upsertMetadata", type_name, components)
+            .await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_deleteMetadata.htm>
+    pub async fn delete_metadata(
+        &mut self,
+        type_name: &str,
+        full_names: &[&str],
+    ) -> Result<Vec<MetadataSaveResult>, Error> {
+        let mut results = vec![];
+        for chunk in full_names.chunks(CRUD_CHUNK_SIZE) {
+            let body = self
+                .call("deleteMetadata", |writer| {
+                    writer.element("type", type_name);
+                    for full_name in chunk {
+                        writer.element("fullNames", full_name);
+                    }
+                })
+                .await;
+            match body.and_then(|body| save_results(&body)) {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(e) => return Err(partial_failure(results, e)),
+            }
+        }
+        Ok(results)
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_renameMetadata.htm>
+    pub async fn rename_metadata(
+        &mut self,
+        type_name: &str,
+        old_full_name: &str,
+        new_full_name: &str,
+    ) -> Result<MetadataSaveResult, Error> {
+        let body = self
+            .call("renameMetadata", |writer| {
+                writer.element("type", type_name);
+                writer.element("oldFullName", old_full_name);
+                writer.element("newFullName", new_full_name);
+            })
+            .await?;
+        let doc = parse_response(&body)?;
+        Ok(MetadataSaveResult::from_xml(first_result(&doc)?))
+    }
+
+    async fn save_metadata<T: Serialize>(
+        &mut self,
+        operation: &str,
+        type_name: &str,
+        components: &[T],
+    ) -> Result<Vec<MetadataSaveResult>, Error> {
+        let components = components
+            .iter()
+            .map(|component| match serde_json::to_value(component)? {
+                Value::Object(fields) => Ok(fields),
+                _ => Err(Error::ConfigError(
+                    "metadata components must serialize to objects".to_string(),
+                )),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut results = vec![];
+        for chunk in components.chunks(CRUD_CHUNK_SIZE) {
+            let body = self
+                .call(operation, |writer| {
+                    for fields in chunk {
+                        writer.start(
+                            "metadata",
+                            &[("xmlns:xsi", XSI_NAMESPACE), ("xsi:type", type_name)],
+                        );
+                        writer.object_fields(fields);
+                        writer.end("metadata");
+                    }
+                })
+                .await;
+            match body.and_then(|body| save_results(&body)) {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(e) => return Err(partial_failure(results, e)),
+            }
+        }
+        Ok(results)
+    }
+
+    async fn call(
+        &mut self,
+        operation: &str,
+        write_body: impl FnOnce(&mut XmlWriter),
+    ) -> Result<String, Error> {
+        let url = self.soap_url()?;
+        soap_call(
+            &mut self.client,
+            url,
+            METADATA_NAMESPACE,
+            operation,
+            write_body,
+        )
+        .await
+    }
+}
+
+fn save_results(body: &str) -> Result<Vec<MetadataSaveResult>, Error> {
+    let doc = parse_response(body)?;
+    Ok(response_results(&doc)
+        .into_iter()
+        .map(MetadataSaveResult::from_xml)
+        .collect())
+}
+
+/// Components of earlier chunks stay saved when a later chunk fails.
+fn partial_failure(results: Vec<MetadataSaveResult>, error: Error) -> Error {
+    if results.is_empty() {
+        error
+    } else {
+        Error::MetadataPartialFailure(results, Box::new(error))
+    }
+}
+
+/// Fails when another poll would end after `deadline`.
+fn check_deadline(
+    operation: &str,
+    id: &str,
+    deadline: Instant,
+    poll_interval: Duration,
+    max_wait: Duration,
+) -> Result<(), Error> {
+    if Instant::now() + poll_interval > deadline {
+        return Err(Error::TimeoutError(format!(
+            "{} {} was not done within {:?}",
+            operation, id, max_wait
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package_manifest::PackageManifest;
+    use crate::requests::deploy_options::DeployTestLevel;
+    use crate::responses::metadata_result::{DeployStatus, RetrieveStatus};
+    use crate::xml::test_support::soap_response;
+    use mockito::{Matcher, Server};
+
+    fn create_test_metadata_api(server_url: &str) -> MetadataApi {
+        let mut client = Client::new();
+        client.set_instance_url(server_url);
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        client.set_version("v60.0");
+        MetadataApi::new(client)
+    }
+
+    #[test]
+    fn test_soap_url() {
+        let api = create_test_metadata_api("https://example.my.salesforce.com");
+        assert_eq!(
+            api.soap_url().unwrap(),
+            "https://example.my.salesforce.com/services/Soap/m/60.0"
+        );
+    }
+
+    #[test]
+    fn test_soap_url_not_logged_in() {
+        let api = MetadataApi::new(Client::new());
+        assert!(matches!(api.soap_url(), Err(Error::NotLoggedIn)));
+    }
+
+    #[tokio::test]
+    async fn test_deploy() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_header("SOAPAction", "\"\"")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("<sessionId>test_token</sessionId>".to_string()),
+                Matcher::Regex("<ZipFile>UEsDBA==</ZipFile>".to_string()),
+                Matcher::Regex("<checkOnly>true</checkOnly>".to_string()),
+                Matcher::Regex("<testLevel>RunLocalTests</testLevel>".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "text/xml")
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "deploy",
+                "<result><done>false</done><id>0Afxx</id><state>Queued</state></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let options = DeployOptions::new()
+            .with_check_only(true)
+            .with_test_level(DeployTestLevel::RunLocalTests);
+        let result = api.deploy(b"PK\x03\x04", &options).await.unwrap();
+        assert_eq!(result.id, "0Afxx");
+        assert!(!result.done);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_deploy_fault() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .with_status(500)
+            .with_body(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+                    <soapenv:Body><soapenv:Fault>
+                        <faultcode>sf:INVALID_SESSION_ID</faultcode>
+                        <faultstring>INVALID_SESSION_ID: Invalid Session ID found in SessionHeader</faultstring>
+                    </soapenv:Fault></soapenv:Body>
+                </soapenv:Envelope>"#,
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api.deploy(b"zip", &DeployOptions::new()).await;
+        match result {
+            Err(Error::SoapFault(fault)) => {
+                assert_eq!(fault.fault_code, "sf:INVALID_SESSION_ID");
+                assert_eq!(fault.code(), "INVALID_SESSION_ID");
+                assert!(fault.fault_string.starts_with("INVALID_SESSION_ID"));
+            }
+            other => panic!("expected SoapFault, got {:?}", other.map(|r| r.id)),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_check_deploy_status() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<checkDeployStatus xmlns=\"http://soap.sforce.com/2006/04/metadata\">\
+                 <asyncProcessId>0Afxx</asyncProcessId><includeDetails>true</includeDetails>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkDeployStatus",
+                "<result><details><componentFailures><componentType>CustomField</componentType>\
+                 <fullName>Account.Tier__c</fullName><problem>Invalid type</problem>\
+                 <problemType>Error</problemType></componentFailures></details>\
+                 <done>true</done><id>0Afxx</id><status>Failed</status><success>false</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api.check_deploy_status("0Afxx", true).await.unwrap();
+        assert_eq!(result.status, DeployStatus::Failed);
+        assert_eq!(result.component_failures[0].full_name, "Account.Tier__c");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_deploy() {
+        let mut server = Server::new_async().await;
+        let in_progress = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<includeDetails>false</includeDetails>".to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkDeployStatus",
+                "<result><done>false</done><id>0Afxx</id><status>InProgress</status></result>",
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let done = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<includeDetails>false</includeDetails>".to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkDeployStatus",
+                "<result><done>true</done><id>0Afxx</id><status>Succeeded</status></result>",
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let details = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeDetails>true</includeDetails>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkDeployStatus",
+                "<result><done>true</done><id>0Afxx</id><numberComponentsDeployed>2</numberComponentsDeployed>\
+                 <status>Succeeded</status><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .wait_for_deploy("0Afxx", Duration::from_millis(1), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.number_components_deployed, 2);
+        in_progress.assert_async().await;
+        done.assert_async().await;
+        details.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_retrieve() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<unpackaged><types><members>Invoice__c</members><name>CustomObject</name></types>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "retrieve",
+                "<result><done>false</done><id>09Sxx</id></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut manifest = PackageManifest::new("60.0");
+        manifest.add("CustomObject", "Invoice__c");
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .retrieve(&RetrieveRequest::new("60.0", manifest))
+            .await
+            .unwrap();
+        assert_eq!(result.id, "09Sxx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_retrieve() {
+        let mut server = Server::new_async().await;
+        let pending = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeZip>false</includeZip>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkRetrieveStatus",
+                "<result><done>false</done><id>09Sxx</id><status>InProgress</status></result>",
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let done = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeZip>false</includeZip>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkRetrieveStatus",
+                "<result><done>true</done><id>09Sxx</id><status>Succeeded</status><success>true</success></result>",
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let with_zip = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeZip>true</includeZip>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkRetrieveStatus",
+                "<result><done>true</done><id>09Sxx</id><status>Succeeded</status>\
+                 <success>true</success><zipFile>UEsDBA==</zipFile></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .wait_for_retrieve("09Sxx", Duration::from_millis(1), Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(result.status, RetrieveStatus::Succeeded);
+        assert_eq!(result.zip_file, Some(b"PK\x03\x04".to_vec()));
+        pending.assert_async().await;
+        done.assert_async().await;
+        with_zip.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_deploy_timeout() {
+        let mut server = Server::new_async().await;
+        let in_progress = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkDeployStatus",
+                "<result><done>false</done><id>0Afxx</id><status>Pending</status></result>",
+            ))
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .wait_for_deploy("0Afxx", Duration::from_millis(5), Duration::from_millis(20))
+            .await;
+        match result {
+            Err(Error::TimeoutError(msg)) => assert!(msg.contains("0Afxx")),
+            other => panic!("Expected TimeoutError, got {:?}", other),
+        }
+        in_progress.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_retrieve_timeout() {
+        let mut server = Server::new_async().await;
+        let pending = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeZip>false</includeZip>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "checkRetrieveStatus",
+                "<result><done>false</done><id>09Sxx</id><status>Pending</status></result>",
+            ))
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        let with_zip = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<includeZip>true</includeZip>".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .wait_for_retrieve("09Sxx", Duration::from_millis(5), Duration::from_millis(20))
+            .await;
+        match result {
+            Err(Error::TimeoutError(msg)) => assert!(msg.contains("09Sxx")),
+            other => panic!("Expected TimeoutError, got {:?}", other),
+        }
+        pending.assert_async().await;
+        with_zip.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<listMetadata xmlns=\"http://soap.sforce.com/2006/04/metadata\">\
+                 <queries><folder>Sales</folder><type>Report</type></queries>\
+                 <asOfVersion>60.0</asOfVersion></listMetadata>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "listMetadata",
+                "<result><fileName>reports/Sales/Pipeline.report</fileName>\
+                 <fullName>Sales/Pipeline</fullName><id>00Oxx</id><type>Report</type></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let properties = api
+            .list_metadata(
+                &[ListMetadataQuery::in_folder("Report", "Sales")],
+                Some("60.0"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].full_name, "Sales/Pipeline");
+        assert_eq!(properties[0].type_name, "Report");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_metadata_chunks_queries() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "listMetadata",
+                "<result><fullName>X</fullName></result>",
+            ))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let queries: Vec<ListMetadataQuery> = ["ApexClass", "ApexPage", "Flow", "Layout"]
+            .iter()
+            .map(|t| ListMetadataQuery::new(t))
+            .collect();
+        let mut api = create_test_metadata_api(&server.url());
+        let properties = api.list_metadata(&queries, None).await.unwrap();
+        assert_eq!(properties.len(), 2);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<type>CustomObject</type><fullNames>Invoice__c</fullNames>\
+                 <fullNames>Missing__c</fullNames>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "readMetadata",
+                r#"<result><records xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CustomObject">
+                    <fullName>Invoice__c</fullName><label>Invoice</label>
+                    <fields><fullName>Amount__c</fullName></fields>
+                    <fields><fullName>Due__c</fullName></fields>
+                </records>
+                <records xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CustomObject"/></result>"#,
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let records = api
+            .read_metadata("CustomObject", &["Invoice__c", "Missing__c"])
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["label"], "Invoice");
+        assert_eq!(records[0]["fields"][1]["fullName"], "Due__c");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<createMetadata xmlns=\"http://soap.sforce.com/2006/04/metadata\">\
+                 <metadata xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:type=\"CustomField\">\
+                 <fullName>Account.Tier__c</fullName><label>Tier &amp; Rank</label>\
+                 <length>20</length><type>Text</type></metadata></createMetadata>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "createMetadata",
+                "<result><fullName>Account.Tier__c</fullName><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let field = serde_json::json!({
+            "fullName": "Account.Tier__c",
+            "label": "Tier & Rank",
+            "length": 20,
+            "type": "Text"
+        });
+        let results = api.create_metadata("CustomField", &[field]).await.unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[0].full_name, "Account.Tier__c");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_metadata_rejects_non_objects() {
+        let mut api = create_test_metadata_api("http://localhost");
+        let result = api
+            .create_metadata("CustomField", &["Account.Tier__c"])
+            .await;
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<updateMetadata ".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "updateMetadata",
+                "<result><errors><message>Not found</message><statusCode>INVALID_CROSS_REFERENCE_KEY</statusCode></errors>\
+                 <fullName>Account.Tier__c</fullName><success>false</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let field = serde_json::json!({"fullName": "Account.Tier__c", "label": "Tier"});
+        let results = api.update_metadata("CustomField", &[field]).await.unwrap();
+        assert!(!results[0].success);
+        assert_eq!(
+            results[0].errors[0].error_code,
+            "INVALID_CROSS_REFERENCE_KEY"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_metadata_chunks() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<upsertMetadata ".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "upsertMetadata",
+                "<result><created>true</created><fullName>X</fullName><success>true</success></result>",
+            ))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let labels: Vec<Value> = (0..12)
+            .map(|i| serde_json::json!({"fullName": format!("Label{}", i), "value": "x"}))
+            .collect();
+        let mut api = create_test_metadata_api(&server.url());
+        let results = api.upsert_metadata("CustomLabel", &labels).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_metadata_later_chunk_fails() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<fullName>Label0</fullName>".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "upsertMetadata",
+                "<result><created>true</created><fullName>Label0</fullName><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex("<fullName>Label10</fullName>".to_string()))
+            .with_status(500)
+            .with_body(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+                    <soapenv:Body><soapenv:Fault>
+                        <faultcode>sf:REQUEST_LIMIT_EXCEEDED</faultcode>
+                        <faultstring>REQUEST_LIMIT_EXCEEDED: too many requests</faultstring>
+                    </soapenv:Fault></soapenv:Body>
+                </soapenv:Envelope>"#,
+            )
+            .create_async()
+            .await;
+
+        let labels: Vec<Value> = (0..12)
+            .map(|i| serde_json::json!({"fullName": format!("Label{}", i), "value": "x"}))
+            .collect();
+        let mut api = create_test_metadata_api(&server.url());
+        match api.upsert_metadata("CustomLabel", &labels).await {
+            Err(Error::MetadataPartialFailure(results, error)) => {
+                assert_eq!(results.len(), 1);
+                assert_eq!(results[0].full_name, "Label0");
+                assert!(matches!(*error, Error::SoapFault(_)));
+            }
+            other => panic!("expected MetadataPartialFailure, got {:?}", other),
+        }
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_metadata_first_chunk_fails() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .with_status(500)
+            .with_body(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+                    <soapenv:Body><soapenv:Fault>
+                        <faultcode>sf:INVALID_SESSION_ID</faultcode>
+                        <faultstring>Invalid Session ID</faultstring>
+                    </soapenv:Fault></soapenv:Body>
+                </soapenv:Envelope>"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let names: Vec<String> = (0..12).map(|i| format!("Label{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api.delete_metadata("CustomLabel", &names).await;
+        assert!(matches!(result, Err(Error::SoapFault(_))));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<deleteMetadata xmlns=\"http://soap.sforce.com/2006/04/metadata\">\
+                 <type>CustomField</type><fullNames>Account.Tier__c</fullNames></deleteMetadata>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "deleteMetadata",
+                "<result><fullName>Account.Tier__c</fullName><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let results = api
+            .delete_metadata("CustomField", &["Account.Tier__c"])
+            .await
+            .unwrap();
+        assert!(results[0].success);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rename_metadata() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .match_body(Matcher::Regex(
+                "<type>CustomObject</type><oldFullName>Invoice__c</oldFullName>\
+                 <newFullName>Bill__c</newFullName>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                METADATA_NAMESPACE,
+                "renameMetadata",
+                "<result><fullName>Bill__c</fullName><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let result = api
+            .rename_metadata("CustomObject", "Invoice__c", "Bill__c")
+            .await
+            .unwrap();
+        assert_eq!(result.full_name, "Bill__c");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_result() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/services/Soap/m/60.0")
+            .with_status(200)
+            .with_body(soap_response(METADATA_NAMESPACE, "retrieve", ""))
+            .create_async()
+            .await;
+
+        let mut api = create_test_metadata_api(&server.url());
+        let request = RetrieveRequest::new("60.0", PackageManifest::new("60.0"));
+        assert!(matches!(
+            api.retrieve(&request).await,
+            Err(Error::XmlError(_))
+        ));
+    }
+}
@@ -1,5 +1,9 @@
+pub mod apex_rest_api;
 pub mod bulk_api;
 pub mod bulk_api_v2;
 #[allow(clippy::module_inception)]
 pub mod client;
+pub mod metadata_api;
 pub mod rest_api;
+pub mod soap_api;
+pub mod tooling_api;
@@ -1,25 +1,139 @@
 use crate::client::client::Client;
+use crate::datetime::format_datetime;
+use crate::describe_cache::{CachedDescribe, DescribeCache, DescribeCacheKey};
 use crate::errors::Error;
+use crate::replication::validate_window;
+use crate::requests::composite_batch_request::{BatchSubrequest, CompositeBatchRequest};
+use crate::requests::content_version_request::{
+    ContentDocumentLinkRequest, ContentVersionMetadata, ShareType,
+};
+use crate::requests::request_options::RequestOptions;
+use crate::responses::action_result::{ActionDescribe, ActionList, ActionResult, ActionSummary};
+use crate::responses::blob_download::BlobDownload;
+use crate::responses::composite_batch_response::CompositeBatchResponse;
+use crate::responses::describe_global_result::DescribeGlobalResult;
+use crate::responses::describe_sobject_result::DescribeSObjectResult;
 use crate::responses::error_response::ErrorResponse;
-use reqwest::Response;
+use crate::responses::explain_response::ExplainResult;
+use crate::responses::limits_response::{OrgLimits, RecordCount, RecordCountResult};
+use crate::responses::publish_result::{publish_error, PublishResult};
+use crate::responses::replication_response::{GetDeletedResult, GetUpdatedResult};
+use crate::responses::save_result::SaveResult;
+use chrono::{DateTime, Utc};
+use reqwest::header::{CONTENT_TYPE, DATE, LAST_MODIFIED};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+/// Maximum number of subrequests Salesforce accepts in a single composite batch.
+const COMPOSITE_BATCH_LIMIT: usize = 25;
+/// Maximum number of records in a single sObject Collections call.
+const COLLECTIONS_LIMIT: usize = 200;
 
 #[derive(Default)]
 pub struct RestApi {
     pub(crate) client: Client,
+    pub(crate) describe_cache: Option<DescribeCache>,
 }
 
-async fn handle_json_response(response: Response) -> Result<Value, Error> {
+pub(crate) async fn handle_json_response<T: DeserializeOwned>(
+    response: Response,
+) -> Result<T, Error> {
     if response.status().is_success() {
-        Ok(response.json::<Value>().await?)
+        Ok(response.json::<T>().await?)
     } else {
         let errors: Vec<ErrorResponse> = response.json().await?;
         Err(Error::ErrorResponses(errors))
     }
 }
 
-async fn handle_empty_response(response: Response) -> Result<(), Error> {
+/// Salesforce names the JSON part of a blob upload after the object, except for ContentVersion.
+fn entity_part_name(sobject_name: &str) -> String {
+    if sobject_name.eq_ignore_ascii_case("ContentVersion") {
+        "entity_content".to_string()
+    } else {
+        format!("entity_{}", sobject_name.to_ascii_lowercase())
+    }
+}
+
+fn fields_params(fields: Option<&[&str]>) -> Vec<(String, String)> {
+    match fields {
+        Some(fields) if !fields.is_empty() => vec![("fields".to_string(), fields.join(","))],
+        _ => vec![],
+    }
+}
+
+/// Percent-encode a value used as one URL path segment, such as a free-text external ID.
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn replication_params(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(String, String)> {
+    vec![
+        ("start".to_string(), format_datetime(&start)),
+        ("end".to_string(), format_datetime(&end)),
+    ]
+}
+
+async fn handle_upsert_response(response: Response) -> Result<SaveResult, Error> {
+    let status = response.status();
+    if status == StatusCode::MULTIPLE_CHOICES {
+        let urls: Vec<String> = response.json().await?;
+        return Err(Error::MultipleChoices(urls));
+    }
+    if !status.is_success() {
+        let errors: Vec<ErrorResponse> = response.json().await?;
+        return Err(Error::ErrorResponses(errors));
+    }
+
+    // Older API versions answer an update with 204 and no body
+    let body = response.text().await?;
+    let mut result = if body.is_empty() {
+        SaveResult {
+            success: true,
+            ..Default::default()
+        }
+    } else {
+        serde_json::from_str::<SaveResult>(&body)?
+    };
+    result.created = status == StatusCode::CREATED;
+    Ok(result)
+}
+
+fn validate_event_name(event_name: &str) -> Result<(), Error> {
+    if event_name.len() > 3 && event_name.ends_with("__e") {
+        Ok(())
+    } else {
+        Err(Error::ConfigError(format!(
+            "{} is not a platform event, its name must end with __e",
+            event_name
+        )))
+    }
+}
+
+async fn handle_publish_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    if response.status().is_success() {
+        Ok(response.json::<T>().await?)
+    } else {
+        let errors: Vec<ErrorResponse> = response.json().await?;
+        Err(publish_error(errors))
+    }
+}
+
+pub(crate) async fn handle_empty_response(response: Response) -> Result<(), Error> {
     if response.status().is_success() {
         Ok(())
     } else {
@@ -30,7 +144,45 @@ async fn handle_empty_response(response: Response) -> Result<(), Error> {
 
 impl RestApi {
     pub fn new(client: Client) -> Self {
-        RestApi { client }
+        RestApi {
+            client,
+            describe_cache: None,
+        }
+    }
+
+    /// Enable caching of `describe` results, revalidated with `If-Modified-Since`.
+    pub fn with_describe_cache(mut self, cache: DescribeCache) -> Self {
+        self.describe_cache = Some(cache);
+        self
+    }
+
+    /// Replace the Salesforce headers (`Sforce-Auto-Assign`, `Sforce-Mru`, ...) sent with each call.
+    pub fn set_request_options(&mut self, options: RequestOptions) -> Result<&mut Self, Error> {
+        self.client.set_request_options(options)?;
+        Ok(self)
+    }
+
+    pub fn describe_cache(&self) -> Option<&DescribeCache> {
+        self.describe_cache.as_ref()
+    }
+
+    /// Forget the cached describe of an object so the next `describe` downloads it again.
+    pub fn invalidate_describe(&mut self, object_name: &str) {
+        let key = self.describe_cache_key(object_name);
+        if let Some(cache) = self.describe_cache.as_mut() {
+            cache.invalidate(&key);
+        }
+    }
+
+    fn describe_cache_key(&self, object_name: &str) -> DescribeCacheKey {
+        // Fall back to the instance URL when the org ID wasn't captured at login
+        let org = self
+            .client
+            .org_id
+            .as_deref()
+            .or(self.client.instance_url.as_deref())
+            .unwrap_or_default();
+        DescribeCacheKey::new(org, &self.client.version, object_name)
     }
 
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_query.htm>
@@ -41,6 +193,16 @@ impl RestApi {
         handle_json_response(response).await
     }
 
+    /// Ask Salesforce how it would run a query, without running it.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/dome_query_explain.htm>
+    pub async fn explain(&mut self, query: &str) -> Result<ExplainResult, Error> {
+        let query_url = format!("{}/query/", self.client.base_path()?);
+        let params = vec![("explain".to_string(), query.to_string())];
+        let response = self.client.get(query_url, params).await?;
+        handle_json_response(response).await
+    }
+
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_queryall.htm>
     pub async fn query_all(&mut self, query: &str) -> Result<Value, Error> {
         let query_url = format!("{}/queryAll/", self.client.base_path()?);
@@ -82,26 +244,139 @@ impl RestApi {
         handle_json_response(response).await
     }
 
+    /// Retrieve a record by ID. Pass `fields` to fetch only those fields instead of every field.
+    ///
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_retrieve_get.htm>
-    pub async fn find_by_id(
+    pub async fn find_by_id<T: DeserializeOwned>(
         &mut self,
         sobject_name: &str,
         id: &str,
-    ) -> Result<Value, Error> {
+        fields: Option<&[&str]>,
+    ) -> Result<T, Error> {
         let resource_url = format!("{}/sobjects/{}/{}", self.client.base_path()?, sobject_name, id);
+        let response = self.client.get(resource_url, fields_params(fields)).await?;
+        handle_json_response(response).await
+    }
+
+    /// Retrieve a record by the value of an external ID field.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_upsert_get.htm>
+    pub async fn find_by_external_id<T: DeserializeOwned>(
+        &mut self,
+        sobject_name: &str,
+        field_name: &str,
+        value: &str,
+    ) -> Result<T, Error> {
+        let resource_url = format!(
+            "{}/sobjects/{}/{}/{}",
+            self.client.base_path()?,
+            sobject_name,
+            field_name,
+            encode_path_segment(value)
+        );
         let response = self.client.get(resource_url, vec![]).await?;
         handle_json_response(response).await
     }
 
+    /// Traverse a relationship from a record, e.g. `Account/{id}/Contacts` or `Contact/{id}/Account`.
+    ///
+    /// A lookup relationship returns the related record; a child relationship returns a query result.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_relationships.htm>
+    pub async fn find_related<T: DeserializeOwned>(
+        &mut self,
+        sobject_name: &str,
+        id: &str,
+        relationship_name: &str,
+        fields: Option<&[&str]>,
+    ) -> Result<T, Error> {
+        let resource_url = format!(
+            "{}/sobjects/{}/{}/{}",
+            self.client.base_path()?,
+            sobject_name,
+            encode_path_segment(id),
+            encode_path_segment(relationship_name)
+        );
+        let response = self.client.get(resource_url, fields_params(fields)).await?;
+        handle_json_response(response).await
+    }
+
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_basic_info_post.htm>
     pub async fn create<T: Serialize>(
         &mut self,
         object_name: &str,
         params: T,
-    ) -> Result<Value, Error> {
+    ) -> Result<SaveResult, Error> {
         let resource_url = format!("{}/sobjects/{}", self.client.base_path()?, object_name);
         let response = self.client.post(resource_url, params, vec![]).await?;
-        handle_json_response(response).await
+        let mut result: SaveResult = handle_json_response(response).await?;
+        result.created = true;
+        Ok(result)
+    }
+
+    /// Publish a platform event such as `Order_Shipped__e`.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.platform_events.meta/platform_events/platform_events_publish_api.htm>
+    pub async fn publish_event<T: Serialize>(
+        &mut self,
+        event_name: &str,
+        payload: &T,
+    ) -> Result<PublishResult, Error> {
+        validate_event_name(event_name)?;
+        let resource_url = format!("{}/sobjects/{}", self.client.base_path()?, event_name);
+        let response = self.client.post(resource_url, payload, vec![]).await?;
+        let result: PublishResult = handle_publish_response(response).await?;
+        result.take_event_uuid().into_result()
+    }
+
+    /// Publish events through sObject Collections, 200 per call. Events that fail are reported
+    /// in their `PublishResult` rather than as an `Err`.
+    ///
+    /// Published events cannot be taken back: when a later call fails, the results of the
+    /// earlier ones are returned in `Error::PublishPartialFailure` together with the error.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_composite_sobjects_collections_create.htm>
+    pub async fn publish_events<T: Serialize>(
+        &mut self,
+        event_name: &str,
+        payloads: &[T],
+    ) -> Result<Vec<PublishResult>, Error> {
+        validate_event_name(event_name)?;
+        let mut records = Vec::with_capacity(payloads.len());
+        for payload in payloads {
+            match serde_json::to_value(payload)? {
+                Value::Object(mut fields) => {
+                    fields.insert("attributes".to_string(), json!({ "type": event_name }));
+                    records.push(Value::Object(fields));
+                }
+                _ => {
+                    return Err(Error::ConfigError(
+                        "event payloads must serialize to objects".to_string(),
+                    ))
+                }
+            }
+        }
+
+        let resource_url = format!("{}/composite/sobjects", self.client.base_path()?);
+        let mut results = Vec::with_capacity(records.len());
+        for chunk in records.chunks(COLLECTIONS_LIMIT) {
+            let body = json!({ "allOrNone": false, "records": chunk });
+            let chunk_results: Result<Vec<PublishResult>, Error> =
+                match self.client.post(resource_url.clone(), body, vec![]).await {
+                    Ok(response) => handle_publish_response(response).await,
+                    Err(e) => Err(e),
+                };
+            match chunk_results {
+                Ok(chunk_results) => results.extend(
+                    chunk_results
+                        .into_iter()
+                        .map(PublishResult::take_event_uuid),
+                ),
+                Err(e) if results.is_empty() => return Err(e),
+                Err(e) => return Err(Error::PublishPartialFailure(results, Box::new(e))),
+            }
+        }
+        Ok(results)
     }
 
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_retrieve_get.htm>
@@ -116,6 +391,10 @@ impl RestApi {
         handle_empty_response(response).await
     }
 
+    /// Insert or update a record by external ID. `SaveResult::created` reports which happened.
+    ///
+    /// Returns `Error::MultipleChoices` when the external ID matches more than one record.
+    ///
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_upsert_patch.htm>
     pub async fn upsert<T: Serialize>(
         &mut self,
@@ -123,7 +402,7 @@ impl RestApi {
         key_name: &str,
         key: &str,
         params: T,
-    ) -> Result<Response, Error> {
+    ) -> Result<SaveResult, Error> {
         let resource_url = format!(
             "{}/sobjects/{}/{}/{}",
             self.client.base_path()?,
@@ -131,7 +410,8 @@ impl RestApi {
             key_name,
             key
         );
-        self.client.patch(resource_url, params).await
+        let response = self.client.patch(resource_url, params).await?;
+        handle_upsert_response(response).await
     }
 
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_retrieve_delete.htm>
@@ -141,19 +421,314 @@ impl RestApi {
         handle_empty_response(response).await
     }
 
+    /// Stream a blob field such as `Attachment.Body`, `Document.Body` or `ContentVersion.VersionData`
+    /// into `writer` chunk by chunk, without buffering the whole file in memory.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_blob_retrieve.htm>
+    pub async fn download_blob<W: AsyncWrite + Unpin>(
+        &mut self,
+        sobject_name: &str,
+        id: &str,
+        field_name: &str,
+        writer: &mut W,
+    ) -> Result<BlobDownload, Error> {
+        let resource_url = format!(
+            "{}/sobjects/{}/{}/{}",
+            self.client.base_path()?,
+            sobject_name,
+            id,
+            field_name
+        );
+        let mut response = self.client.get_raw(&resource_url, vec![]).await?;
+        if !response.status().is_success() {
+            let errors: Vec<ErrorResponse> = response.json().await?;
+            return Err(Error::ErrorResponses(errors));
+        }
+
+        let mut download = BlobDownload {
+            content_type: response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string()),
+            content_length: response.content_length(),
+            bytes_written: 0,
+        };
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            download.bytes_written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(download)
+    }
+
+    /// Create a record with a blob field, e.g. `Document.Body` or `Attachment.Body`, using a
+    /// multipart request. `metadata` holds the other fields; `reader` is streamed as the file part.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/dome_sobject_insert_update_blob.htm>
+    pub async fn upload_blob<T, R>(
+        &mut self,
+        sobject_name: &str,
+        blob_field: &str,
+        file_name: &str,
+        metadata: T,
+        reader: R,
+    ) -> Result<SaveResult, Error>
+    where
+        T: Serialize,
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        let resource_url = format!("{}/sobjects/{}", self.client.base_path()?, sobject_name);
+        let entity = Part::text(serde_json::to_string(&metadata)?).mime_str("application/json")?;
+        let file = Part::stream(Body::wrap_stream(ReaderStream::new(reader)))
+            .file_name(file_name.to_string())
+            .mime_str("application/octet-stream")?;
+        let form = Form::new()
+            .part(entity_part_name(sobject_name), entity)
+            .part(blob_field.to_string(), file);
+        let response = self
+            .client
+            .post_multipart(resource_url, form, vec![])
+            .await?;
+        let mut result: SaveResult = handle_json_response(response).await?;
+        result.created = true;
+        Ok(result)
+    }
+
+    /// Upload a file as a new `ContentVersion`, streaming `reader` as `VersionData`.
+    ///
+    /// Set `metadata.content_document_id` to add a version to an existing file.
+    pub async fn upload_content_version<R>(
+        &mut self,
+        metadata: &ContentVersionMetadata,
+        reader: R,
+    ) -> Result<SaveResult, Error>
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        let file_name = metadata.path_on_client.clone();
+        self.upload_blob(
+            "ContentVersion",
+            "VersionData",
+            &file_name,
+            metadata,
+            reader,
+        )
+        .await
+    }
+
+    /// Share the file behind a `ContentVersion` with another record by creating a `ContentDocumentLink`.
+    pub async fn link_content_version(
+        &mut self,
+        content_version_id: &str,
+        linked_entity_id: &str,
+        share_type: ShareType,
+    ) -> Result<SaveResult, Error> {
+        let version: Value = self
+            .find_by_id(
+                "ContentVersion",
+                content_version_id,
+                Some(&["ContentDocumentId"]),
+            )
+            .await?;
+        let content_document_id = version["ContentDocumentId"].as_str().ok_or_else(|| {
+            Error::ConfigError(format!(
+                "ContentVersion {} has no ContentDocumentId",
+                content_version_id
+            ))
+        })?;
+        let link = ContentDocumentLinkRequest {
+            content_document_id,
+            linked_entity_id,
+            share_type,
+        };
+        self.create("ContentDocumentLink", link).await
+    }
+
+    /// IDs of records updated between `start` and `end`, which must lie within the last 30 days.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_getupdated.htm>
+    pub async fn get_updated(
+        &mut self,
+        sobject_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<GetUpdatedResult, Error> {
+        validate_window(start, end, Utc::now())?;
+        let resource_url = format!(
+            "{}/sobjects/{}/updated/",
+            self.client.base_path()?,
+            sobject_name
+        );
+        let response = self
+            .client
+            .get(resource_url, replication_params(start, end))
+            .await?;
+        handle_json_response(response).await
+    }
+
+    /// Records deleted between `start` and `end`, which must lie within the last 30 days.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_getdeleted.htm>
+    pub async fn get_deleted(
+        &mut self,
+        sobject_name: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<GetDeletedResult, Error> {
+        validate_window(start, end, Utc::now())?;
+        let resource_url = format!(
+            "{}/sobjects/{}/deleted/",
+            self.client.base_path()?,
+            sobject_name
+        );
+        let response = self
+            .client
+            .get(resource_url, replication_params(start, end))
+            .await?;
+        handle_json_response(response).await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_limits.htm>
+    pub async fn limits(&mut self) -> Result<OrgLimits, Error> {
+        let resource_url = format!("{}/limits", self.client.base_path()?);
+        let response = self.client.get(resource_url, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    /// Approximate record counts. An empty `sobject_names` returns counts for every object.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_record_count.htm>
+    pub async fn record_count(
+        &mut self,
+        sobject_names: &[&str],
+    ) -> Result<Vec<RecordCount>, Error> {
+        let resource_url = format!("{}/limits/recordCount", self.client.base_path()?);
+        let params = if sobject_names.is_empty() {
+            vec![]
+        } else {
+            vec![("sObjects".to_string(), sobject_names.join(","))]
+        };
+        let response = self.client.get(resource_url, params).await?;
+        let result: RecordCountResult = handle_json_response(response).await?;
+        Ok(result.s_objects)
+    }
+
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_describeGlobal.htm>
-    pub async fn describe_global(&mut self) -> Result<Value, Error> {
+    pub async fn describe_global(&mut self) -> Result<DescribeGlobalResult, Error> {
         let resource_url = format!("{}/sobjects", self.client.base_path()?);
         let response = self.client.get(resource_url, vec![]).await?;
         handle_json_response(response).await
     }
 
+    /// Served from the describe cache when one is enabled and Salesforce answers `304 Not Modified`.
+    ///
     /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_describe.htm>
-    pub async fn describe(&mut self, object_name: &str) -> Result<Value, Error> {
+    pub async fn describe(&mut self, object_name: &str) -> Result<DescribeSObjectResult, Error> {
         let resource_url = format!("{}/sobjects/{}/describe", self.client.base_path()?, object_name);
+        let key = self.describe_cache_key(object_name);
+        let cached = self
+            .describe_cache
+            .as_mut()
+            .and_then(|cache| cache.get(&key))
+            .cloned();
+
+        let headers = match &cached {
+            Some(entry) => vec![("If-Modified-Since".to_string(), entry.last_modified.clone())],
+            None => vec![],
+        };
+        let response = self
+            .client
+            .get_with_headers(resource_url, vec![], headers)
+            .await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                log::debug!("Describe of {} not modified, using cache.", object_name);
+                return Ok(entry.describe);
+            }
+        }
+
+        let last_modified = response
+            .headers()
+            .get(LAST_MODIFIED)
+            .or_else(|| response.headers().get(DATE))
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let describe: DescribeSObjectResult = handle_json_response(response).await?;
+
+        if let (Some(cache), Some(last_modified)) = (self.describe_cache.as_mut(), last_modified) {
+            cache.insert(
+                key,
+                CachedDescribe {
+                    last_modified,
+                    describe: describe.clone(),
+                },
+            );
+        }
+        Ok(describe)
+    }
+
+    /// Execute up to 25 independent subrequests in a single call.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_composite_batch.htm>
+    pub async fn composite_batch(
+        &mut self,
+        requests: Vec<BatchSubrequest>,
+        halt_on_error: bool,
+    ) -> Result<CompositeBatchResponse, Error> {
+        if requests.len() > COMPOSITE_BATCH_LIMIT {
+            return Err(Error::ConfigError(format!(
+                "composite batch accepts at most {} subrequests, got {}",
+                COMPOSITE_BATCH_LIMIT,
+                requests.len()
+            )));
+        }
+        let resource_url = format!("{}/composite/batch", self.client.base_path()?);
+        let body = CompositeBatchRequest::new(&self.client.version, requests, halt_on_error);
+        let response = self.client.post(resource_url, body, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    /// Actions in a category such as `standard`, `custom/flow` or `custom/apex`.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_action.meta/api_action/actions_intro_invoking.htm>
+    pub async fn list_actions(&mut self, category: &str) -> Result<Vec<ActionSummary>, Error> {
+        let resource_url = format!("{}/actions/{}", self.client.base_path()?, category);
+        let response = self.client.get(resource_url, vec![]).await?;
+        let list: ActionList = handle_json_response(response).await?;
+        Ok(list.actions)
+    }
+
+    /// `name` includes the category, e.g. `standard/emailSimple` or `custom/flow/Onboard_Customer`.
+    pub async fn describe_action(&mut self, name: &str) -> Result<ActionDescribe, Error> {
+        let resource_url = format!("{}/actions/{}", self.client.base_path()?, name);
         let response = self.client.get(resource_url, vec![]).await?;
         handle_json_response(response).await
     }
+
+    /// Invoke an action once per element of `inputs`, e.g. `custom/flow/Onboard_Customer`.
+    /// Failed items are reported in their `ActionResult` rather than as an `Err`.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_action.meta/api_action/actions_obj_invoke.htm>
+    pub async fn invoke_action<I: Serialize, T: DeserializeOwned>(
+        &mut self,
+        name: &str,
+        inputs: &[I],
+    ) -> Result<Vec<ActionResult<T>>, Error> {
+        let resource_url = format!("{}/actions/{}", self.client.base_path()?, name);
+        let body = json!({ "inputs": inputs });
+        let response = self.client.post(resource_url, body, vec![]).await?;
+        if response.status().is_success() {
+            return Ok(response.json().await?);
+        }
+        // Salesforce answers 400 when any item fails, still with one result per input.
+        let text = response.text().await?;
+        match serde_json::from_str::<Vec<ActionResult<T>>>(&text) {
+            Ok(results) => Ok(results),
+            Err(_) => Err(Error::ErrorResponses(serde_json::from_str(&text)?)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -235,6 +810,128 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_request_options_sent_with_calls() {
+        use crate::requests::request_options::{AutoAssign, DuplicateRuleHeader};
+
+        let mut server = Server::new_async().await;
+        let query = server
+            .mock("GET", "/services/data/v60.0/query/")
+            .match_query(mockito::Matcher::Any)
+            .match_header("Sforce-Query-Options", "batchSize=1000")
+            .match_header("Sforce-Call-Options", "client=my-app")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"totalSize": 0, "done": true, "records": []}).to_string())
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/services/data/v60.0/sobjects/Lead")
+            .match_header("Sforce-Auto-Assign", "FALSE")
+            .match_header(
+                "Sforce-Duplicate-Rule-Header",
+                "allowSave=true, includeRecordDetails=false, runAsCurrentUser=false",
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "00Qxx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        api.set_request_options(
+            RequestOptions::new()
+                .with_query_batch_size(1000)
+                .unwrap()
+                .with_call_options_client("my-app"),
+        )
+        .unwrap();
+        api.query("SELECT Id FROM Lead").await.unwrap();
+
+        api.set_request_options(
+            RequestOptions::new()
+                .with_auto_assign(AutoAssign::None)
+                .with_duplicate_rule(DuplicateRuleHeader {
+                    allow_save: true,
+                    ..Default::default()
+                }),
+        )
+        .unwrap();
+        let res = api
+            .create("Lead", json!({"LastName": "Doe"}))
+            .await
+            .unwrap();
+        assert_eq!(res.id, "00Qxx");
+        query.assert_async().await;
+        create.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_explain() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/query/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "explain".into(),
+                "SELECT Id FROM Account WHERE Name = 'Acme'".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "plans": [{
+                        "cardinality": 1,
+                        "fields": ["Name"],
+                        "leadingOperationType": "Index",
+                        "notes": [],
+                        "relativeCost": 0.0007,
+                        "sobjectCardinality": 2843,
+                        "sobjectType": "Account"
+                    }],
+                    "sourceQuery": "SELECT Id FROM Account WHERE Name = 'Acme'"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api
+            .explain("SELECT Id FROM Account WHERE Name = 'Acme'")
+            .await
+            .unwrap();
+        let plan = res.best_plan().unwrap();
+        assert_eq!(plan.fields, vec!["Name"]);
+        assert_eq!(plan.sobject_cardinality, 2843);
+        assert!(!res.warn_if_table_scan());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_explain_malformed_query() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/query/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"message": "unexpected token: FORM", "errorCode": "MALFORMED_QUERY"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        match api.explain("SELECT Id FORM Account").await {
+            Err(Error::ErrorResponses(errors)) => {
+                assert_eq!(errors[0].error_code, "MALFORMED_QUERY")
+            }
+            other => panic!("Expected ErrorResponses, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_query_all() {
         let mut server = Server::new_async().await;
@@ -360,34 +1057,474 @@ mod tests {
             .await;
 
         let mut api = create_test_rest_api(&server.url());
-        let res = api.find_by_id("Account", "001xx000003DGbX").await.unwrap();
+        let res: Value = api
+            .find_by_id("Account", "001xx000003DGbX", None)
+            .await
+            .unwrap();
         assert_eq!(res["Id"], "001xx000003DGbX");
         assert_eq!(res["Name"], "Acme");
         mock.assert_async().await;
     }
 
+    #[derive(serde::Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    struct Account {
+        id: String,
+        name: String,
+    }
+
     #[tokio::test]
-    async fn test_create() {
+    async fn test_find_by_id_with_fields() {
         let mut server = Server::new_async().await;
         let mock = server
-            .mock("POST", "/services/data/v60.0/sobjects/Account")
-            .with_status(201)
+            .mock("GET", "/services/data/v60.0/sobjects/Account/001xx")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "fields".into(),
+                "Id,Name".into(),
+            ))
+            .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(json!({"id": "001xx000003DGbX", "success": true}).to_string())
+            .with_body(json!({"Id": "001xx", "Name": "Acme"}).to_string())
             .create_async()
             .await;
 
         let mut api = create_test_rest_api(&server.url());
-        let mut params = std::collections::HashMap::new();
-        params.insert("Name", "Test Account");
-        let res = api.create("Account", params).await.unwrap();
-        assert_eq!(res["id"], "001xx000003DGbX");
-        assert_eq!(res["success"], true);
+        let account: Account = api
+            .find_by_id("Account", "001xx", Some(&["Id", "Name"]))
+            .await
+            .unwrap();
+        assert_eq!(account.id, "001xx");
+        assert_eq!(account.name, "Acme");
         mock.assert_async().await;
     }
 
     #[tokio::test]
-    async fn test_update() {
+    async fn test_find_by_id_not_found() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/001xx")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"errorCode": "NOT_FOUND", "message": "The requested resource does not exist"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api.find_by_id::<Account>("Account", "001xx", None).await;
+        match res.unwrap_err() {
+            Error::ErrorResponses(errors) => assert_eq!(errors[0].error_code, "NOT_FOUND"),
+            e => panic!("Expected ErrorResponses, got {:?}", e),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_by_external_id() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/sobjects/Account/ExternalId__c/ext123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"Id": "001xx", "Name": "Acme"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let account: Account = api
+            .find_by_external_id("Account", "ExternalId__c", "ext123")
+            .await
+            .unwrap();
+        assert_eq!(account.id, "001xx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_by_external_id_encodes_value() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/sobjects/Account/ExternalId__c/A%2FB%3Fc%20d%23%25%2B",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"Id": "001xx", "Name": "Acme"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let account: Account = api
+            .find_by_external_id("Account", "ExternalId__c", "A/B?c d#%+")
+            .await
+            .unwrap();
+        assert_eq!(account.id, "001xx");
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(encode_path_segment("ext-123_a.b~"), "ext-123_a.b~");
+        assert_eq!(encode_path_segment("A/B?c"), "A%2FB%3Fc");
+        assert_eq!(encode_path_segment("Grüße"), "Gr%C3%BC%C3%9Fe");
+    }
+
+    #[tokio::test]
+    async fn test_find_related() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Contact/003xx/Account")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "fields".into(),
+                "Id,Name".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"Id": "001xx", "Name": "Acme"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let account: Account = api
+            .find_related("Contact", "003xx", "Account", Some(&["Id", "Name"]))
+            .await
+            .unwrap();
+        assert_eq!(account.name, "Acme");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_blob() {
+        let mut server = Server::new_async().await;
+        let body: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+        let mock = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/sobjects/ContentVersion/068xx/VersionData",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/pdf")
+            .with_body(body.clone())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut out: Vec<u8> = Vec::new();
+        let download = api
+            .download_blob("ContentVersion", "068xx", "VersionData", &mut out)
+            .await
+            .unwrap();
+        assert_eq!(download.content_type, Some("application/pdf".to_string()));
+        assert_eq!(download.content_length, Some(body.len() as u64));
+        assert_eq!(download.bytes_written, body.len() as u64);
+        assert_eq!(out, body);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_blob_not_found() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Attachment/00Pxx/Body")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(json!([{"errorCode": "NOT_FOUND", "message": "missing"}]).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut out: Vec<u8> = Vec::new();
+        let res = api
+            .download_blob("Attachment", "00Pxx", "Body", &mut out)
+            .await;
+        assert!(matches!(res, Err(Error::ErrorResponses(_))));
+        assert!(out.is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_content_version() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/ContentVersion")
+            .match_header(
+                "content-type",
+                mockito::Matcher::Regex("^multipart/form-data".to_string()),
+            )
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(
+                    r#"name="entity_content"\r\nContent-Type: application/json"#.to_string(),
+                ),
+                mockito::Matcher::Regex(r#""PathOnClient":"q3.pdf""#.to_string()),
+                mockito::Matcher::Regex(
+                    r#"name="VersionData"; filename="q3.pdf"\r\nContent-Type: application/octet-stream\r\n\r\n%PDF-1.7"#
+                        .to_string(),
+                ),
+            ]))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "068xx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let metadata = ContentVersionMetadata::new("Q3 Report", "q3.pdf");
+        let reader = std::io::Cursor::new(b"%PDF-1.7 body".to_vec());
+        let res = api.upload_content_version(&metadata, reader).await.unwrap();
+        assert_eq!(res.id, "068xx");
+        assert!(res.created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_blob_document() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/Document")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#"name="entity_document""#.to_string()),
+                mockito::Matcher::Regex(r#"name="Body"; filename="logo.png""#.to_string()),
+            ]))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "015xx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let metadata = json!({"Name": "logo", "FolderId": "00lxx"});
+        let reader = std::io::Cursor::new(vec![0u8; 1024]);
+        let res = api
+            .upload_blob("Document", "Body", "logo.png", metadata, reader)
+            .await
+            .unwrap();
+        assert_eq!(res.id, "015xx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_link_content_version() {
+        let mut server = Server::new_async().await;
+        let lookup = server
+            .mock("GET", "/services/data/v60.0/sobjects/ContentVersion/068xx")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "fields".into(),
+                "ContentDocumentId".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"ContentDocumentId": "069xx"}).to_string())
+            .create_async()
+            .await;
+        let link = server
+            .mock("POST", "/services/data/v60.0/sobjects/ContentDocumentLink")
+            .match_body(mockito::Matcher::Json(json!({
+                "ContentDocumentId": "069xx",
+                "LinkedEntityId": "001xx",
+                "ShareType": "V"
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "06Axx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api
+            .link_content_version("068xx", "001xx", ShareType::Viewer)
+            .await
+            .unwrap();
+        assert_eq!(res.id, "06Axx");
+        lookup.assert_async().await;
+        link.assert_async().await;
+    }
+
+    #[test]
+    fn test_entity_part_name() {
+        assert_eq!(entity_part_name("ContentVersion"), "entity_content");
+        assert_eq!(entity_part_name("Document"), "entity_document");
+        assert_eq!(entity_part_name("Attachment"), "entity_attachment");
+    }
+
+    #[tokio::test]
+    async fn test_get_updated() {
+        let mut server = Server::new_async().await;
+        let end = Utc::now();
+        let start = end - chrono::Duration::hours(1);
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/updated/")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("start".into(), format_datetime(&start)),
+                mockito::Matcher::UrlEncoded("end".into(), format_datetime(&end)),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"ids": ["001xx"], "latestDateCovered": "2024-01-01T00:00:00.000+0000"})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api.get_updated("Account", start, end).await.unwrap();
+        assert_eq!(res.ids, vec!["001xx".to_string()]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_deleted() {
+        let mut server = Server::new_async().await;
+        let end = Utc::now();
+        let start = end - chrono::Duration::hours(1);
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/deleted/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "deletedRecords": [{"id": "001xx", "deletedDate": "2024-01-01T00:00:00.000+0000"}],
+                    "earliestDateAvailable": "2023-12-01T00:00:00.000+0000",
+                    "latestDateCovered": "2024-01-01T00:00:00.000+0000"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api.get_deleted("Account", start, end).await.unwrap();
+        assert_eq!(res.deleted_records[0].id, "001xx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_updated_outside_window() {
+        let mut api = create_test_rest_api("https://na1.salesforce.com");
+        let end = Utc::now();
+        let start = end - chrono::Duration::days(31);
+        let res = api.get_updated("Account", start, end).await;
+        assert!(matches!(res, Err(Error::DateRangeError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/limits")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "DailyApiRequests": {"Max": 15000, "Remaining": 14998},
+                    "DailyBulkV2QueryJobs": {"Max": 10000, "Remaining": 50}
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let limits = api.limits().await.unwrap();
+        assert_eq!(limits.get("DailyApiRequests").unwrap().remaining, 14998);
+        assert!(limits.is_low("DailyBulkV2QueryJobs", 0.05));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_record_count() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/limits/recordCount")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "sObjects".into(),
+                "Account,Contact".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"sObjects": [{"count": 3, "name": "Account"}, {"count": 7, "name": "Contact"}]})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let counts = api.record_count(&["Account", "Contact"]).await.unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].name, "Account");
+        assert_eq!(counts[1].count, 7);
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_fields_params() {
+        assert!(fields_params(None).is_empty());
+        assert!(fields_params(Some(&[])).is_empty());
+        assert_eq!(
+            fields_params(Some(&["Id", "Name"])),
+            vec![("fields".to_string(), "Id,Name".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/Account")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "001xx000003DGbX", "success": true}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut params = std::collections::HashMap::new();
+        params.insert("Name", "Test Account");
+        let res = api.create("Account", params).await.unwrap();
+        assert_eq!(res.id, "001xx000003DGbX");
+        assert!(res.success);
+        assert!(res.created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_failure() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/Account")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{
+                    "message": "Required fields are missing: [Name]",
+                    "errorCode": "REQUIRED_FIELD_MISSING",
+                    "fields": ["Name"]
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let params: std::collections::HashMap<&str, &str> = std::collections::HashMap::new();
+        match api.create("Account", params).await.unwrap_err() {
+            Error::ErrorResponses(errors) => {
+                assert_eq!(errors[0].error_code, "REQUIRED_FIELD_MISSING")
+            }
+            e => panic!("Expected ErrorResponses, got {:?}", e),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_update() {
         let mut server = Server::new_async().await;
         let mock = server
             .mock("PATCH", "/services/data/v60.0/sobjects/Account/001xx")
@@ -404,16 +1541,103 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_upsert() {
+    async fn test_upsert() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "PATCH",
+                "/services/data/v60.0/sobjects/Account/ExternalId__c/ext123",
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"id": "001xx", "success": true, "errors": [], "created": true}).to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut params = std::collections::HashMap::new();
+        params.insert("Name", "Upserted");
+        let res = api
+            .upsert("Account", "ExternalId__c", "ext123", params)
+            .await
+            .unwrap();
+        assert_eq!(res.id, "001xx");
+        assert!(res.created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_updated() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "PATCH",
+                "/services/data/v60.0/sobjects/Account/ExternalId__c/ext123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"id": "001xx", "success": true, "errors": [], "created": false}).to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut params = std::collections::HashMap::new();
+        params.insert("Name", "Upserted");
+        let res = api
+            .upsert("Account", "ExternalId__c", "ext123", params)
+            .await
+            .unwrap();
+        assert_eq!(res.id, "001xx");
+        assert!(res.success);
+        assert!(!res.created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_no_content() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "PATCH",
+                "/services/data/v60.0/sobjects/Account/ExternalId__c/ext123",
+            )
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let mut params = std::collections::HashMap::new();
+        params.insert("Name", "Upserted");
+        let res = api
+            .upsert("Account", "ExternalId__c", "ext123", params)
+            .await
+            .unwrap();
+        assert!(res.success);
+        assert!(!res.created);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upsert_multiple_choices() {
         let mut server = Server::new_async().await;
         let mock = server
             .mock(
                 "PATCH",
                 "/services/data/v60.0/sobjects/Account/ExternalId__c/ext123",
             )
-            .with_status(201)
+            .with_status(300)
             .with_header("content-type", "application/json")
-            .with_body(json!({"id": "001xx", "success": true}).to_string())
+            .with_body(
+                json!([
+                    "/services/data/v60.0/sobjects/Account/001xx1",
+                    "/services/data/v60.0/sobjects/Account/001xx2"
+                ])
+                .to_string(),
+            )
             .create_async()
             .await;
 
@@ -423,7 +1647,13 @@ mod tests {
         let res = api
             .upsert("Account", "ExternalId__c", "ext123", params)
             .await;
-        assert!(res.is_ok());
+        match res.unwrap_err() {
+            Error::MultipleChoices(urls) => {
+                assert_eq!(urls.len(), 2);
+                assert_eq!(urls[1], "/services/data/v60.0/sobjects/Account/001xx2");
+            }
+            e => panic!("Expected MultipleChoices, got {:?}", e),
+        }
         mock.assert_async().await;
     }
 
@@ -462,8 +1692,8 @@ mod tests {
 
         let mut api = create_test_rest_api(&server.url());
         let res = api.describe_global().await.unwrap();
-        assert_eq!(res["encoding"], "UTF-8");
-        assert_eq!(res["maxBatchSize"], 200);
+        assert_eq!(res.encoding, "UTF-8");
+        assert_eq!(res.max_batch_size, 200);
         mock.assert_async().await;
     }
 
@@ -486,8 +1716,486 @@ mod tests {
 
         let mut api = create_test_rest_api(&server.url());
         let res = api.describe("Account").await.unwrap();
-        assert_eq!(res["name"], "Account");
-        assert_eq!(res["createable"], true);
+        assert_eq!(res.name, "Account");
+        assert!(res.createable);
+        mock.assert_async().await;
+    }
+    #[tokio::test]
+    async fn test_composite_batch() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/composite/batch")
+            .match_body(mockito::Matcher::Json(json!({
+                "haltOnError": false,
+                "batchRequests": [
+                    {"method": "GET", "url": "v60.0/limits"},
+                    {"method": "PATCH", "url": "v60.0/sobjects/Account/001xx", "richInput": {"Name": "Acme"}}
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "hasErrors": false,
+                    "results": [
+                        {"statusCode": 200, "result": {"DailyApiRequests": {"Max": 15000, "Remaining": 14000}}},
+                        {"statusCode": 204, "result": null}
+                    ]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let res = api
+            .composite_batch(
+                vec![
+                    BatchSubrequest::get("limits"),
+                    BatchSubrequest::patch("sobjects/Account/001xx", json!({"Name": "Acme"})),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(!res.has_errors);
+        assert_eq!(res.results.len(), 2);
+        assert_eq!(res.results[0].status_code, 200);
+        assert_eq!(res.results[1].status_code, 204);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_composite_batch_too_many_requests() {
+        let mut api = create_test_rest_api("https://na1.salesforce.com");
+        let requests = (0..26).map(|_| BatchSubrequest::get("limits")).collect();
+        match api.composite_batch(requests, false).await.unwrap_err() {
+            Error::ConfigError(msg) => assert!(msg.contains("at most 25")),
+            e => panic!("Expected ConfigError, got {:?}", e),
+        }
+    }
+    fn describe_body() -> String {
+        json!({"name": "Account", "fields": [{"name": "Id", "type": "id"}]}).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_describe_cache_revalidates_with_if_modified_since() {
+        let mut server = Server::new_async().await;
+        let fresh = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/describe")
+            .match_header("If-Modified-Since", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("Last-Modified", "Mon, 01 Jan 2024 00:00:00 GMT")
+            .with_body(describe_body())
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/describe")
+            .match_header("If-Modified-Since", "Mon, 01 Jan 2024 00:00:00 GMT")
+            .with_status(304)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url()).with_describe_cache(DescribeCache::new());
+        api.client.set_org_id("00Dxx");
+
+        let first = api.describe("Account").await.unwrap();
+        assert_eq!(first.fields.len(), 1);
+        assert_eq!(api.describe_cache().unwrap().len(), 1);
+
+        for _ in 0..2 {
+            let cached = api.describe("Account").await.unwrap();
+            assert_eq!(cached.name, "Account");
+            assert_eq!(cached.fields.len(), 1);
+        }
+
+        fresh.assert_async().await;
+        not_modified.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_describe_cache_invalidate() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/describe")
+            .match_header("If-Modified-Since", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("Last-Modified", "Mon, 01 Jan 2024 00:00:00 GMT")
+            .with_body(describe_body())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url()).with_describe_cache(DescribeCache::new());
+        api.describe("Account").await.unwrap();
+        api.invalidate_describe("account");
+        assert!(api.describe_cache().unwrap().is_empty());
+        api.describe("Account").await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_describe_without_cache_never_revalidates() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/describe")
+            .match_header("If-Modified-Since", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("Last-Modified", "Mon, 01 Jan 2024 00:00:00 GMT")
+            .with_body(describe_body())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        api.describe("Account").await.unwrap();
+        api.describe("Account").await.unwrap();
+        assert!(api.describe_cache().is_none());
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_actions() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/actions/custom/flow")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"actions": [{
+                    "label": "Onboard Customer",
+                    "name": "Onboard_Customer",
+                    "type": "FLOW",
+                    "url": "/services/data/v60.0/actions/custom/flow/Onboard_Customer"
+                }]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let actions = api.list_actions("custom/flow").await.unwrap();
+        assert_eq!(actions[0].name, "Onboard_Customer");
+        assert_eq!(actions[0].action_type, "FLOW");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_describe_action() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/actions/standard/emailSimple")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "name": "emailSimple",
+                    "label": "Send Email",
+                    "type": "EMAILSIMPLE",
+                    "inputs": [{"name": "emailBody", "type": "TEXTAREA", "required": true}],
+                    "outputs": []
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let describe = api.describe_action("standard/emailSimple").await.unwrap();
+        assert_eq!(describe.inputs[0].name, "emailBody");
+        assert!(describe.inputs[0].required);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invoke_action() {
+        #[derive(serde::Deserialize)]
+        struct Outputs {
+            #[serde(rename = "Flow__InterviewStatus")]
+            status: String,
+        }
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "POST",
+                "/services/data/v60.0/actions/custom/flow/Onboard_Customer",
+            )
+            .match_body(mockito::Matcher::Json(json!({
+                "inputs": [{"accountId": "001A"}, {"accountId": "001B"}]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {"actionName": "Onboard_Customer", "errors": null, "isSuccess": true,
+                     "outputValues": {"Flow__InterviewStatus": "Finished"}},
+                    {"actionName": "Onboard_Customer", "errors": null, "isSuccess": true,
+                     "outputValues": {"Flow__InterviewStatus": "Paused"}}
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let inputs = vec![json!({"accountId": "001A"}), json!({"accountId": "001B"})];
+        let results: Vec<ActionResult<Outputs>> = api
+            .invoke_action("custom/flow/Onboard_Customer", &inputs)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].output_values.as_ref().unwrap().status, "Paused");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invoke_action_item_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/actions/custom/apex/RecalculateScore")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{
+                    "actionName": "RecalculateScore",
+                    "errors": [{"statusCode": "INVALID_ID_FIELD", "message": "bad id", "fields": []}],
+                    "isSuccess": false,
+                    "outputValues": null
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let results: Vec<ActionResult<Value>> = api
+            .invoke_action("custom/apex/RecalculateScore", &[json!({"recordId": "x"})])
+            .await
+            .unwrap();
+        assert!(!results[0].is_success);
+        assert_eq!(results[0].errors[0].error_code, "INVALID_ID_FIELD");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invoke_action_not_found() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/actions/custom/flow/Missing")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"errorCode": "NOT_FOUND", "message": "The requested resource does not exist"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let result: Result<Vec<ActionResult<Value>>, Error> =
+            api.invoke_action("custom/flow/Missing", &[json!({})]).await;
+        match result {
+            Err(Error::ErrorResponses(errors)) => assert_eq!(errors[0].error_code, "NOT_FOUND"),
+            other => panic!("expected ErrorResponses, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_event() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/Order_Shipped__e")
+            .match_body(mockito::Matcher::Json(json!({"Order_Number__c": "A-100"})))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "e00xx0000000001AAA",
+                    "success": true,
+                    "errors": [{
+                        "statusCode": "OPERATION_ENQUEUED",
+                        "message": "08fb2a4e-5b1c-4a0e-9a39-7c5bbd0f1f3c",
+                        "fields": []
+                    }]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let result = api
+            .publish_event("Order_Shipped__e", &json!({"Order_Number__c": "A-100"}))
+            .await
+            .unwrap();
+        assert_eq!(result.id, "e00xx0000000001AAA");
+        assert_eq!(
+            result.event_uuid.as_deref(),
+            Some("08fb2a4e-5b1c-4a0e-9a39-7c5bbd0f1f3c")
+        );
+        assert!(result.errors.is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_rejects_non_event() {
+        let mut api = create_test_rest_api("http://localhost");
+        match api.publish_event("Account", &json!({})).await {
+            Err(Error::ConfigError(msg)) => assert!(msg.contains("__e")),
+            other => panic!("expected ConfigError, got {:?}", other),
+        }
+        assert!(api.publish_events("__e", &[json!({})]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_failure() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/sobjects/Order_Shipped__e")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{
+                    "errorCode": "PLATFORM_EVENT_PUBLISH_FAILED",
+                    "message": "Failed to publish platform events"
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        match api.publish_event("Order_Shipped__e", &json!({})).await {
+            Err(Error::PublishError(errors)) => {
+                assert_eq!(errors[0].error_code, "PLATFORM_EVENT_PUBLISH_FAILED")
+            }
+            other => panic!("expected PublishError, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_events() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/composite/sobjects")
+            .match_body(mockito::Matcher::Json(json!({
+                "allOrNone": false,
+                "records": [
+                    {"attributes": {"type": "Order_Shipped__e"}, "Order_Number__c": "A-1"},
+                    {"attributes": {"type": "Order_Shipped__e"}, "Order_Number__c": "A-2"}
+                ]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {
+                        "id": "e00xx0000000001AAA",
+                        "success": true,
+                        "errors": [{"statusCode": "OPERATION_ENQUEUED", "message": "uuid-1", "fields": []}]
+                    },
+                    {
+                        "success": false,
+                        "errors": [{"statusCode": "PLATFORM_EVENT_PUBLISH_FAILED", "message": "boom", "fields": []}]
+                    }
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let results = api
+            .publish_events(
+                "Order_Shipped__e",
+                &[
+                    json!({"Order_Number__c": "A-1"}),
+                    json!({"Order_Number__c": "A-2"}),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[0].event_uuid.as_deref(), Some("uuid-1"));
+        assert!(matches!(
+            results[1].clone().into_result(),
+            Err(Error::PublishError(_))
+        ));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_events_chunks() {
+        let mut server = Server::new_async().await;
+        let result = json!({"id": "e00xx", "success": true, "errors": []});
+        let mock = server
+            .mock("POST", "/services/data/v60.0/composite/sobjects")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(move |request| {
+                let body: Value = serde_json::from_slice(request.body().unwrap()).unwrap();
+                let count = body["records"].as_array().unwrap().len();
+                Value::Array(vec![result.clone(); count]).to_string().into()
+            })
+            .expect(2)
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let payloads = vec![json!({"Order_Number__c": "A"}); 250];
+        let results = api
+            .publish_events("Order_Shipped__e", &payloads)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 250);
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_publish_events_later_chunk_fails() {
+        let mut server = Server::new_async().await;
+        let result = json!({"id": "e00xx", "success": true, "errors": []});
+        let first = server
+            .mock("POST", "/services/data/v60.0/composite/sobjects")
+            .match_body(mockito::Matcher::Regex(r#""A-0""#.to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(Value::Array(vec![result; 200]).to_string())
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/services/data/v60.0/composite/sobjects")
+            .match_body(mockito::Matcher::Regex(r#""A-200""#.to_string()))
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"errorCode": "UNKNOWN_EXCEPTION", "message": "An unexpected error occurred"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_rest_api(&server.url());
+        let payloads: Vec<Value> = (0..250)
+            .map(|i| json!({"Order_Number__c": format!("A-{}", i)}))
+            .collect();
+        match api.publish_events("Order_Shipped__e", &payloads).await {
+            Err(Error::PublishPartialFailure(results, error)) => {
+                assert_eq!(results.len(), 200);
+                assert!(matches!(*error, Error::ErrorResponses(_)));
+            }
+            other => panic!("expected PublishPartialFailure, got {:?}", other),
+        }
+        first.assert_async().await;
+        second.assert_async().await;
+    }
 }
@@ -0,0 +1,763 @@
+use crate::client::client::Client;
+use crate::errors::Error;
+use crate::requests::partner_request::{LeadConvert, MergeRequest, ProcessRequest};
+use crate::responses::error_response::ErrorResponse;
+use crate::responses::partner_result::{LeadConvertResult, MergeResult, ProcessResult, UserInfo};
+use crate::responses::save_result::SaveResult;
+use crate::responses::soap_fault::SoapFault;
+use crate::xml::{child_string, children_named, XmlWriter};
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+const PARTNER_NAMESPACE: &str = "urn:partner.soap.sforce.com";
+const SOAP_ENVELOPE_NAMESPACE: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+
+/// `merge` accepts at most 200 requests, the other batch calls at most 200 IDs.
+const BATCH_SIZE: usize = 200;
+/// `convertLead` accepts at most 100 leads per call.
+const LEAD_CONVERT_BATCH_SIZE: usize = 100;
+
+/// SOAP Partner API calls that have no REST equivalent.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_list.htm>
+#[derive(Default)]
+pub struct SoapApi {
+    pub(crate) client: Client,
+}
+
+impl SoapApi {
+    pub fn new(client: Client) -> Self {
+        SoapApi { client }
+    }
+
+    /// `{instance_url}/services/Soap/u/{version}`, with the version number lacking the `v` prefix.
+    pub(crate) fn soap_url(&self) -> Result<String, Error> {
+        let instance_url = self
+            .client
+            .instance_url
+            .as_ref()
+            .ok_or(Error::NotLoggedIn)?;
+        Ok(format!(
+            "{}/services/Soap/u/{}",
+            instance_url,
+            self.client.version.trim_start_matches('v')
+        ))
+    }
+
+    /// Merge one or two duplicates into `master_id`, then apply `field_overrides` to the master.
+    /// An unsuccessful merge is returned as `Error::ErrorResponses`.
+    pub async fn merge<T: Serialize>(
+        &mut self,
+        sobject_type: &str,
+        master_id: &str,
+        duplicate_ids: &[&str],
+        field_overrides: &T,
+    ) -> Result<MergeResult, Error> {
+        let field_overrides = match serde_json::to_value(field_overrides)? {
+            Value::Object(fields) => fields,
+            Value::Null => Map::new(),
+            _ => {
+                return Err(Error::ConfigError(
+                    "field overrides must serialize to an object".to_string(),
+                ))
+            }
+        };
+        let request = MergeRequest {
+            field_overrides,
+            ..MergeRequest::new(sobject_type, master_id, duplicate_ids)
+        };
+        request.validate()?;
+        let result = self
+            .merge_records(&[request])
+            .await?
+            .pop()
+            .ok_or_else(|| Error::XmlError("response has no result".to_string()))?;
+        if result.success {
+            Ok(result)
+        } else {
+            Err(Error::ErrorResponses(result.errors))
+        }
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_merge.htm>
+    pub async fn merge_records(
+        &mut self,
+        requests: &[MergeRequest],
+    ) -> Result<Vec<MergeResult>, Error> {
+        let mut results = vec![];
+        for chunk in requests.chunks(BATCH_SIZE) {
+            let body = self
+                .call("merge", |writer| {
+                    for request in chunk {
+                        request.write_xml(writer);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            results.extend(
+                response_results(&doc)
+                    .into_iter()
+                    .map(MergeResult::from_xml),
+            );
+        }
+        Ok(results)
+    }
+
+    /// Convert a single lead. An unsuccessful conversion is returned as `Error::ErrorResponses`.
+    pub async fn convert_lead(
+        &mut self,
+        lead_convert: LeadConvert,
+    ) -> Result<LeadConvertResult, Error> {
+        lead_convert.validate()?;
+        let result = self
+            .convert_leads(&[lead_convert])
+            .await?
+            .pop()
+            .ok_or_else(|| Error::XmlError("response has no result".to_string()))?;
+        if result.success {
+            Ok(result)
+        } else {
+            Err(Error::ErrorResponses(result.errors))
+        }
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_convertlead.htm>
+    pub async fn convert_leads(
+        &mut self,
+        lead_converts: &[LeadConvert],
+    ) -> Result<Vec<LeadConvertResult>, Error> {
+        let mut results = vec![];
+        for chunk in lead_converts.chunks(LEAD_CONVERT_BATCH_SIZE) {
+            let body = self
+                .call("convertLead", |writer| {
+                    for lead_convert in chunk {
+                        lead_convert.write_xml(writer);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            results.extend(
+                response_results(&doc)
+                    .into_iter()
+                    .map(LeadConvertResult::from_xml),
+            );
+        }
+        Ok(results)
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_getservertimestamp.htm>
+    pub async fn get_server_timestamp(&mut self) -> Result<DateTime<Utc>, Error> {
+        let body = self.call("getServerTimestamp", |_| {}).await?;
+        let doc = parse_response(&body)?;
+        let timestamp = child_string(first_result(&doc)?, "timestamp").unwrap_or_default();
+        DateTime::parse_from_rfc3339(&timestamp)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| Error::XmlError(format!("invalid timestamp {}: {}", timestamp, e)))
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_setpassword.htm>
+    pub async fn set_password(&mut self, user_id: &str, password: &str) -> Result<(), Error> {
+        self.call("setPassword", |writer| {
+            writer.element("userId", user_id);
+            writer.element("password", password);
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the generated password; Salesforce does not email it to the user.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_resetpassword.htm>
+    pub async fn reset_password(&mut self, user_id: &str) -> Result<String, Error> {
+        let body = self
+            .call("resetPassword", |writer| {
+                writer.element("userId", user_id);
+            })
+            .await?;
+        let doc = parse_response(&body)?;
+        child_string(first_result(&doc)?, "password")
+            .ok_or_else(|| Error::XmlError("result has no password".to_string()))
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_emptyrecyclebin.htm>
+    pub async fn empty_recycle_bin(&mut self, ids: &[String]) -> Result<Vec<SaveResult>, Error> {
+        self.id_call("emptyRecycleBin", ids).await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_undelete.htm>
+    pub async fn undelete(&mut self, ids: &[String]) -> Result<Vec<SaveResult>, Error> {
+        self.id_call("undelete", ids).await
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_process.htm>
+    pub async fn process(
+        &mut self,
+        requests: &[ProcessRequest],
+    ) -> Result<Vec<ProcessResult>, Error> {
+        let mut results = vec![];
+        for chunk in requests.chunks(BATCH_SIZE) {
+            let body = self
+                .call("process", |writer| {
+                    for request in chunk {
+                        request.write_xml(writer);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            results.extend(
+                response_results(&doc)
+                    .into_iter()
+                    .map(ProcessResult::from_xml),
+            );
+        }
+        Ok(results)
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_getuserinfo.htm>
+    pub async fn get_user_info(&mut self) -> Result<UserInfo, Error> {
+        let body = self.call("getUserInfo", |_| {}).await?;
+        let doc = parse_response(&body)?;
+        Ok(UserInfo::from_xml(first_result(&doc)?))
+    }
+
+    async fn id_call(&mut self, operation: &str, ids: &[String]) -> Result<Vec<SaveResult>, Error> {
+        let mut results = vec![];
+        for chunk in ids.chunks(BATCH_SIZE) {
+            let body = self
+                .call(operation, |writer| {
+                    for id in chunk {
+                        writer.element("ids", id);
+                    }
+                })
+                .await?;
+            let doc = parse_response(&body)?;
+            results.extend(response_results(&doc).into_iter().map(SaveResult::from_xml));
+        }
+        Ok(results)
+    }
+
+    async fn call(
+        &mut self,
+        operation: &str,
+        write_body: impl FnOnce(&mut XmlWriter),
+    ) -> Result<String, Error> {
+        let url = self.soap_url()?;
+        soap_call(
+            &mut self.client,
+            url,
+            PARTNER_NAMESPACE,
+            operation,
+            write_body,
+        )
+        .await
+    }
+}
+
+/// Send a SOAP operation with a session header in `namespace` and return the response body.
+/// Faults become `Error::SoapFault`.
+pub(crate) async fn soap_call(
+    client: &mut Client,
+    url: String,
+    namespace: &str,
+    operation: &str,
+    write_body: impl FnOnce(&mut XmlWriter),
+) -> Result<String, Error> {
+    client.ensure_refresh().await?;
+    let session_id = client
+        .access_token_value()
+        .ok_or(Error::NotLoggedIn)?
+        .to_string();
+
+    let mut writer = XmlWriter::new();
+    writer.start("env:Envelope", &[("xmlns:env", SOAP_ENVELOPE_NAMESPACE)]);
+    writer.start("env:Header", &[]);
+    writer.start("SessionHeader", &[("xmlns", namespace)]);
+    writer.element("sessionId", &session_id);
+    writer.end("SessionHeader");
+    writer.end("env:Header");
+    writer.start("env:Body", &[]);
+    writer.start(operation, &[("xmlns", namespace)]);
+    write_body(&mut writer);
+    writer.end(operation);
+    writer.end("env:Body");
+    writer.end("env:Envelope");
+
+    let headers = vec![
+        (
+            "Content-Type".to_string(),
+            "text/xml; charset=UTF-8".to_string(),
+        ),
+        ("SOAPAction".to_string(), "\"\"".to_string()),
+    ];
+    let res = client
+        .post_raw_buffer(url, writer.into_string().into_bytes(), headers)
+        .await?;
+
+    let status = res.status();
+    let body = res.text().await?;
+    if status.is_success() {
+        Ok(body)
+    } else if let Some(fault) = SoapFault::parse(&body) {
+        Err(Error::SoapFault(fault))
+    } else {
+        Err(Error::ErrorResponses(vec![ErrorResponse {
+            message: status.to_string(),
+            error_code: status.as_str().to_string(),
+            fields: None,
+        }]))
+    }
+}
+
+pub(crate) fn parse_response(body: &str) -> Result<Document<'_>, Error> {
+    Document::parse(body).map_err(|e| Error::XmlError(e.to_string()))
+}
+
+/// The `<result>` elements of a `<{operation}Response>`.
+pub(crate) fn response_results<'a, 'input>(doc: &'a Document<'input>) -> Vec<Node<'a, 'input>> {
+    doc.descendants()
+        .find(|n| n.has_tag_name("Body"))
+        .and_then(|body| body.first_element_child())
+        .map(|response| children_named(response, "result").collect())
+        .unwrap_or_default()
+}
+
+pub(crate) fn first_result<'a, 'input>(
+    doc: &'a Document<'input>,
+) -> Result<Node<'a, 'input>, Error> {
+    response_results(doc)
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::XmlError("response has no result".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::partner_request::ProcessAction;
+    use crate::xml::test_support::soap_response;
+    use chrono::TimeZone;
+    use mockito::{Matcher, Server};
+
+    fn create_test_soap_api(server_url: &str) -> SoapApi {
+        let mut client = Client::new();
+        client.set_instance_url(server_url);
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        client.set_version("v60.0");
+        SoapApi::new(client)
+    }
+
+    #[test]
+    fn test_soap_url() {
+        let api = create_test_soap_api("https://example.my.salesforce.com");
+        assert_eq!(
+            api.soap_url().unwrap(),
+            "https://example.my.salesforce.com/services/Soap/u/60.0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_records() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(
+                    "<SessionHeader xmlns=\"urn:partner.soap.sforce.com\"><sessionId>test_token"
+                        .to_string(),
+                ),
+                Matcher::Regex("<sf:Id>001A</sf:Id></masterRecord>".to_string()),
+                Matcher::Regex("<recordToMergeIds>001B</recordToMergeIds>".to_string()),
+            ]))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "merge",
+                "<result><id>001A</id><mergedRecordIds>001B</mergedRecordIds>\
+                 <success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let results = api
+            .merge_records(&[MergeRequest::new("Account", "001A", &["001B"])])
+            .await
+            .unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[0].merged_record_ids, ["001B"]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_convert_leads() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<convertLead xmlns=\"urn:partner.soap.sforce.com\"><leadConverts>".to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "convertLead",
+                "<result><accountId>001A</accountId><contactId>003A</contactId>\
+                 <leadId>00QA</leadId><opportunityId>006A</opportunityId>\
+                 <success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let results = api
+            .convert_leads(&[LeadConvert::new("00QA", "Closed - Converted")])
+            .await
+            .unwrap();
+        assert_eq!(results[0].opportunity_id.as_deref(), Some("006A"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_merge_with_field_overrides() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<sf:type>Contact</sf:type><sf:fieldsToNull>Phone</sf:fieldsToNull>\
+                 <sf:Id>003A</sf:Id><sf:Email>keep@acme.test</sf:Email></masterRecord>\
+                 <recordToMergeIds>003B</recordToMergeIds><recordToMergeIds>003C</recordToMergeIds>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "merge",
+                "<result><id>003A</id><mergedRecordIds>003B</mergedRecordIds>\
+                 <mergedRecordIds>003C</mergedRecordIds><success>true</success>\
+                 <updatedRelatedIds>500A</updatedRelatedIds></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let overrides = serde_json::json!({"Email": "keep@acme.test", "Phone": null});
+        let result = api
+            .merge("Contact", "003A", &["003B", "003C"], &overrides)
+            .await
+            .unwrap();
+        assert_eq!(result.merged_record_ids, ["003B", "003C"]);
+        assert_eq!(result.updated_related_ids, ["500A"]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_merge_failure_is_an_error() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "merge",
+                "<result><errors><message>entity is deleted</message>\
+                 <statusCode>ENTITY_IS_DELETED</statusCode></errors>\
+                 <id>001A</id><success>false</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        match api.merge("Account", "001A", &["001B"], &()).await {
+            Err(Error::ErrorResponses(errors)) => {
+                assert_eq!(errors[0].error_code, "ENTITY_IS_DELETED")
+            }
+            other => panic!("expected ErrorResponses, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_merge_validates_before_sending() {
+        let mut api = create_test_soap_api("http://localhost");
+        let result = api
+            .merge("Account", "001A", &["001B", "001C", "001D"], &())
+            .await;
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+        let result = api.merge("Account", "001A", &["001B"], &"Name").await;
+        assert!(matches!(result, Err(Error::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_convert_lead() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<accountId>001A</accountId>.*<doNotCreateOpportunity>true</doNotCreateOpportunity>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "convertLead",
+                r#"<result xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+                    <accountId>001A</accountId><contactId>003N</contactId><leadId>00QA</leadId>
+                    <opportunityId xsi:nil="true"/><success>true</success></result>"#,
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let result = api
+            .convert_lead(
+                LeadConvert::new("00QA", "Closed - Converted")
+                    .with_account_id("001A")
+                    .without_opportunity(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.account_id.as_deref(), Some("001A"));
+        assert_eq!(result.contact_id.as_deref(), Some("003N"));
+        assert_eq!(result.opportunity_id, None);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_convert_lead_failure_is_an_error() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "convertLead",
+                "<result><errors><message>invalid converted status</message>\
+                 <statusCode>INVALID_STATUS</statusCode></errors>\
+                 <leadId>00QA</leadId><success>false</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let result = api.convert_lead(LeadConvert::new("00QA", "Open")).await;
+        assert!(matches!(result, Err(Error::ErrorResponses(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_server_timestamp() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<getServerTimestamp xmlns=\"urn:partner.soap.sforce.com\"></getServerTimestamp>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "getServerTimestamp",
+                "<result><timestamp>2024-03-01T12:30:00.000Z</timestamp></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let timestamp = api.get_server_timestamp().await.unwrap();
+        assert_eq!(
+            timestamp,
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap()
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_set_password_escapes_password() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<userId>005A</userId><password>a&lt;b&amp;c</password>".to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(PARTNER_NAMESPACE, "setPassword", "<result/>"))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        api.set_password("005A", "a<b&c").await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_reset_password() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "resetPassword",
+                "<result><password>Xy7&amp;pq</password></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        assert_eq!(api.reset_password("005A").await.unwrap(), "Xy7&pq");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_empty_recycle_bin() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "<emptyRecycleBin xmlns=\"urn:partner.soap.sforce.com\"><ids>001A</ids><ids>001B</ids>"
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "emptyRecycleBin",
+                "<result><id>001A</id><success>true</success></result>\
+                 <result><errors><message>not deleted</message>\
+                 <statusCode>INVALID_ID_FIELD</statusCode></errors>\
+                 <id>001B</id><success>false</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let results = api
+            .empty_recycle_bin(&["001A".to_string(), "001B".to_string()])
+            .await
+            .unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[1].errors[0].error_code, "INVALID_ID_FIELD");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_undelete() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex("<undelete ".to_string()))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "undelete",
+                "<result><id>001A</id><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let results = api.undelete(&["001A".to_string()]).await.unwrap();
+        assert_eq!(results[0].id, "001A");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_process() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .match_body(Matcher::Regex(
+                "xsi:type=\"ProcessWorkitemRequest\"><action>Approve</action>".to_string(),
+            ))
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "process",
+                "<result><entityId>006A</entityId><instanceId>04gA</instanceId>\
+                 <instanceStatus>Approved</instanceStatus><success>true</success></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let results = api
+            .process(&[ProcessRequest::workitem("04iA", ProcessAction::Approve)])
+            .await
+            .unwrap();
+        assert_eq!(results[0].instance_status.as_deref(), Some("Approved"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_user_info() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(200)
+            .with_body(soap_response(
+                PARTNER_NAMESPACE,
+                "getUserInfo",
+                "<result><organizationId>00DA</organizationId><userId>005A</userId>\
+                 <userName>admin@acme.test</userName></result>",
+            ))
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        let info = api.get_user_info().await.unwrap();
+        assert_eq!(info.organization_id, "00DA");
+        assert_eq!(info.user_name, "admin@acme.test");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fault_is_structured() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(500)
+            .with_body(
+                r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:sf="urn:fault.partner.soap.sforce.com">
+                    <soapenv:Body><soapenv:Fault>
+                        <faultcode>sf:INVALID_ID_FIELD</faultcode>
+                        <faultstring>INVALID_ID_FIELD: invalid user id</faultstring>
+                        <detail><sf:InvalidIdFault>
+                            <sf:exceptionCode>INVALID_ID_FIELD</sf:exceptionCode>
+                            <sf:exceptionMessage>invalid user id</sf:exceptionMessage>
+                        </sf:InvalidIdFault></detail>
+                    </soapenv:Fault></soapenv:Body>
+                </soapenv:Envelope>"#,
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        match api.reset_password("bad").await {
+            Err(Error::SoapFault(fault)) => {
+                assert_eq!(fault.code(), "INVALID_ID_FIELD");
+                assert_eq!(fault.fault_type.as_deref(), Some("InvalidIdFault"));
+            }
+            other => panic!("expected SoapFault, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_error_without_fault() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/services/Soap/u/60.0")
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .create_async()
+            .await;
+
+        let mut api = create_test_soap_api(&server.url());
+        match api.get_user_info().await {
+            Err(Error::ErrorResponses(errors)) => assert_eq!(errors[0].error_code, "503"),
+            other => panic!("expected ErrorResponses, got {:?}", other),
+        }
+    }
+}
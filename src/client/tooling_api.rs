@@ -0,0 +1,1141 @@
+use crate::apex_tests::{ClassCoverage, TestMethodResult, TestRunSummary};
+use crate::client::client::Client;
+use crate::client::rest_api::{handle_empty_response, handle_json_response};
+use crate::datetime::format_soql_datetime;
+use crate::errors::Error;
+use crate::requests::run_tests_request::RunTestsRequest;
+use crate::requests::trace_flag_request::{DebugLevel, TraceFlag};
+use crate::responses::apex_log::ApexLog;
+use crate::responses::apex_test_response::{
+    ApexCodeCoverageAggregateRecord, ApexCodeCoverageRecord, ApexTestQueueItem,
+    ApexTestResultRecord, RunTestsResult,
+};
+use crate::responses::describe_global_result::DescribeGlobalResult;
+use crate::responses::describe_sobject_result::DescribeSObjectResult;
+use crate::responses::error_response::ErrorResponse;
+use crate::responses::execute_anonymous_result::ExecuteAnonymousResult;
+use crate::responses::query_result::QueryResult;
+use crate::responses::save_result::SaveResult;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Tooling API: metadata objects such as `ApexClass`, `ApexTrigger`, `ApexCodeCoverage` and `ApexLog`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources.htm>
+#[derive(Default)]
+pub struct ToolingApi {
+    pub(crate) client: Client,
+}
+
+impl ToolingApi {
+    pub fn new(client: Client) -> Self {
+        ToolingApi { client }
+    }
+
+    pub(crate) fn base_path(&self) -> Result<String, Error> {
+        Ok(format!("{}/tooling", self.client.base_path()?))
+    }
+
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources.htm>
+    pub async fn query<T: DeserializeOwned>(&mut self, query: &str) -> Result<T, Error> {
+        let query_url = format!("{}/query/", self.base_path()?);
+        let params = vec![("q".to_string(), query.to_string())];
+        let response = self.client.get(query_url, params).await?;
+        handle_json_response(response).await
+    }
+
+    /// Fetch the next page of a query using its `nextRecordsUrl`.
+    pub async fn query_more<T: DeserializeOwned>(
+        &mut self,
+        next_records_url: &str,
+    ) -> Result<T, Error> {
+        let instance_url = self
+            .client
+            .instance_url
+            .as_ref()
+            .ok_or(Error::NotLoggedIn)?;
+        let query_url = format!("{}/{}", instance_url, next_records_url);
+        let response = self.client.get(query_url, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    pub async fn find_by_id<T: DeserializeOwned>(
+        &mut self,
+        sobject_name: &str,
+        id: &str,
+    ) -> Result<T, Error> {
+        let resource_url = format!("{}/sobjects/{}/{}", self.base_path()?, sobject_name, id);
+        let response = self.client.get(resource_url, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    pub async fn create<T: Serialize>(
+        &mut self,
+        sobject_name: &str,
+        params: T,
+    ) -> Result<SaveResult, Error> {
+        let resource_url = format!("{}/sobjects/{}", self.base_path()?, sobject_name);
+        let response = self.client.post(resource_url, params, vec![]).await?;
+        let mut result: SaveResult = handle_json_response(response).await?;
+        result.created = true;
+        Ok(result)
+    }
+
+    pub async fn update<T: Serialize>(
+        &mut self,
+        sobject_name: &str,
+        id: &str,
+        params: T,
+    ) -> Result<(), Error> {
+        let resource_url = format!("{}/sobjects/{}/{}", self.base_path()?, sobject_name, id);
+        let response = self.client.patch(resource_url, params).await?;
+        handle_empty_response(response).await
+    }
+
+    pub async fn destroy(&mut self, sobject_name: &str, id: &str) -> Result<(), Error> {
+        let resource_url = format!("{}/sobjects/{}/{}", self.base_path()?, sobject_name, id);
+        let response = self.client.delete(resource_url).await?;
+        handle_empty_response(response).await
+    }
+
+    pub async fn describe_global(&mut self) -> Result<DescribeGlobalResult, Error> {
+        let resource_url = format!("{}/sobjects", self.base_path()?);
+        let response = self.client.get(resource_url, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    pub async fn describe(&mut self, sobject_name: &str) -> Result<DescribeSObjectResult, Error> {
+        let resource_url = format!("{}/sobjects/{}/describe", self.base_path()?, sobject_name);
+        let response = self.client.get(resource_url, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    /// Compile and run a block of anonymous Apex. Compile errors and uncaught exceptions
+    /// are reported in the result rather than as an `Err`.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources.htm>
+    pub async fn execute_anonymous(&mut self, apex: &str) -> Result<ExecuteAnonymousResult, Error> {
+        let resource_url = format!("{}/executeAnonymous/", self.base_path()?);
+        let params = vec![("anonymousBody".to_string(), apex.to_string())];
+        let response = self.client.get(resource_url, params).await?;
+        handle_json_response(response).await
+    }
+
+    /// Queue Apex tests and return the `AsyncApexJob` Id without waiting for them.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources_runtestsasynchronous.htm>
+    pub async fn run_tests_asynchronous(
+        &mut self,
+        request: &RunTestsRequest,
+    ) -> Result<String, Error> {
+        let resource_url = format!("{}/runTestsAsynchronous/", self.base_path()?);
+        let response = self.client.post(resource_url, request, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    /// Run Apex tests and wait for the result in the same request. Salesforce limits this
+    /// to a single test class.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources_runtestssynchronous.htm>
+    pub async fn run_tests_synchronous(
+        &mut self,
+        request: &RunTestsRequest,
+    ) -> Result<RunTestsResult, Error> {
+        let resource_url = format!("{}/runTestsSynchronous/", self.base_path()?);
+        let response = self.client.post(resource_url, request, vec![]).await?;
+        handle_json_response(response).await
+    }
+
+    /// Queue Apex tests, then poll every `poll_interval` until all of them finished or
+    /// `max_wait` elapsed.
+    pub async fn run_tests(
+        &mut self,
+        request: &RunTestsRequest,
+        poll_interval: Duration,
+        max_wait: Duration,
+    ) -> Result<TestRunSummary, Error> {
+        let job_id = self.run_tests_asynchronous(request).await?;
+        let with_coverage = !request.skip_code_coverage;
+        self.wait_for_test_run(&job_id, poll_interval, max_wait, with_coverage)
+            .await
+    }
+
+    /// Poll `ApexTestQueueItem` until every class of the job finished, then collect the
+    /// `ApexTestResult` records and, optionally, code coverage of the classes under test.
+    ///
+    /// Returns `Error::TimeoutError` when the job has not finished after `max_wait`.
+    pub async fn wait_for_test_run(
+        &mut self,
+        job_id: &str,
+        poll_interval: Duration,
+        max_wait: Duration,
+        with_coverage: bool,
+    ) -> Result<TestRunSummary, Error> {
+        let deadline = Instant::now() + max_wait;
+        let escaped_job_id = soql_escape(job_id);
+        let queue_items = loop {
+            let items: Vec<ApexTestQueueItem> = self
+                .query_records(&format!(
+                    "SELECT Id, ApexClassId, ApexClass.Name, Status, ExtendedStatus \
+                     FROM ApexTestQueueItem WHERE ParentJobId = '{}'",
+                    escaped_job_id
+                ))
+                .await?;
+            // The queue items may not exist yet right after the job was queued
+            if !items.is_empty() && items.iter().all(|item| item.status.is_finished()) {
+                break items;
+            }
+            if Instant::now() + poll_interval > deadline {
+                return Err(Error::TimeoutError(format!(
+                    "test run {} did not finish within {:?}",
+                    job_id, max_wait
+                )));
+            }
+            tokio::time::sleep(poll_interval).await;
+        };
+
+        let results: Vec<ApexTestResultRecord> = self
+            .query_records(&format!(
+                "SELECT ApexClass.Name, MethodName, Outcome, Message, StackTrace, RunTime \
+                 FROM ApexTestResult WHERE AsyncApexJobId = '{}'",
+                escaped_job_id
+            ))
+            .await?;
+
+        let coverage = if with_coverage {
+            let class_ids: Vec<String> = queue_items
+                .iter()
+                .map(|item| item.apex_class_id.clone())
+                .collect();
+            self.coverage_for_test_classes(&class_ids).await?
+        } else {
+            vec![]
+        };
+
+        Ok(TestRunSummary {
+            job_id: Some(job_id.to_string()),
+            tests: results.into_iter().map(TestMethodResult::from).collect(),
+            coverage,
+        })
+    }
+
+    /// Aggregate coverage of every class or trigger touched by the given test classes.
+    async fn coverage_for_test_classes(
+        &mut self,
+        test_class_ids: &[String],
+    ) -> Result<Vec<ClassCoverage>, Error> {
+        if test_class_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let per_test: Vec<ApexCodeCoverageRecord> = self
+            .query_records(&format!(
+                "SELECT ApexClassOrTriggerId FROM ApexCodeCoverage WHERE ApexTestClassId IN ({})",
+                soql_in_list(test_class_ids)
+            ))
+            .await?;
+        let mut covered_ids: Vec<String> = per_test
+            .into_iter()
+            .map(|record| record.apex_class_or_trigger_id)
+            .collect();
+        covered_ids.sort();
+        covered_ids.dedup();
+        if covered_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let aggregates: Vec<ApexCodeCoverageAggregateRecord> = self
+            .query_records(&format!(
+                "SELECT ApexClassOrTrigger.Name, NumLinesCovered, NumLinesUncovered \
+                 FROM ApexCodeCoverageAggregate WHERE ApexClassOrTriggerId IN ({})",
+                soql_in_list(&covered_ids)
+            ))
+            .await?;
+        Ok(aggregates
+            .into_iter()
+            .map(|record| ClassCoverage {
+                name: record
+                    .apex_class_or_trigger
+                    .map(|c| c.name)
+                    .unwrap_or_default(),
+                lines_covered: record.num_lines_covered,
+                lines_uncovered: record.num_lines_uncovered,
+            })
+            .collect())
+    }
+
+    pub async fn create_debug_level(
+        &mut self,
+        debug_level: &DebugLevel,
+    ) -> Result<SaveResult, Error> {
+        self.create("DebugLevel", debug_level).await
+    }
+
+    /// Start logging, e.g. `TraceFlag::for_user(user_id, debug_level_id, Duration::hours(1))`.
+    pub async fn create_trace_flag(&mut self, trace_flag: &TraceFlag) -> Result<SaveResult, Error> {
+        self.create("TraceFlag", trace_flag).await
+    }
+
+    /// Debug logs started at or after `since`, optionally only those of one user, oldest first.
+    pub async fn list_logs(
+        &mut self,
+        since: Option<DateTime<Utc>>,
+        user_id: Option<&str>,
+    ) -> Result<Vec<ApexLog>, Error> {
+        let mut conditions = vec![];
+        if let Some(since) = since {
+            conditions.push(format!("StartTime >= {}", format_soql_datetime(&since)));
+        }
+        if let Some(user_id) = user_id {
+            conditions.push(format!("LogUserId = '{}'", soql_escape(user_id)));
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        self.query_records(&format!(
+            "SELECT Id, LogUserId, LogLength, Operation, Request, Status, StartTime, \
+             DurationMilliseconds, Application, Location FROM ApexLog{} \
+             ORDER BY StartTime ASC, Id ASC",
+            filter
+        ))
+        .await
+    }
+
+    /// Download the raw text of a debug log.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apexlog.htm>
+    pub async fn log_body(&mut self, log_id: &str) -> Result<String, Error> {
+        let resource_url = format!("{}/sobjects/ApexLog/{}/Body", self.base_path()?, log_id);
+        let response = self.client.get_raw(&resource_url, vec![]).await?;
+        if !response.status().is_success() {
+            let errors: Vec<ErrorResponse> = response.json().await?;
+            return Err(Error::ErrorResponses(errors));
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Delete debug logs in batches of 200. Each log gets its own `SaveResult`; one failed
+    /// delete does not stop the others.
+    ///
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_composite_sobjects_collections_delete.htm>
+    pub async fn delete_logs(&mut self, log_ids: &[String]) -> Result<Vec<SaveResult>, Error> {
+        let mut results = vec![];
+        for ids in log_ids.chunks(DELETE_BATCH_SIZE) {
+            let resource_url = format!(
+                "{}/composite/sobjects?ids={}&allOrNone=false",
+                self.client.base_path()?,
+                ids.join(",")
+            );
+            let response = self.client.delete(resource_url).await?;
+            let mut batch: Vec<SaveResult> = handle_json_response(response).await?;
+            results.append(&mut batch);
+        }
+        Ok(results)
+    }
+
+    /// Delete every debug log that started before `before`.
+    pub async fn delete_logs_before(
+        &mut self,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<SaveResult>, Error> {
+        let logs: Vec<ApexLogId> = self
+            .query_records(&format!(
+                "SELECT Id FROM ApexLog WHERE StartTime < {}",
+                format_soql_datetime(&before)
+            ))
+            .await?;
+        let ids: Vec<String> = logs.into_iter().map(|log| log.id).collect();
+        self.delete_logs(&ids).await
+    }
+
+    /// Run a query and follow `nextRecordsUrl` until every record has been fetched.
+    pub(crate) async fn query_records<T: DeserializeOwned>(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<T>, Error> {
+        let mut page: QueryResult<T> = self.query(query).await?;
+        let mut records = std::mem::take(&mut page.records);
+        while let Some(next_records_url) = page.next_records_url.take().filter(|_| !page.done) {
+            page = self
+                .query_more(next_records_url.trim_start_matches('/'))
+                .await?;
+            records.append(&mut page.records);
+        }
+        Ok(records)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApexLogId {
+    id: String,
+}
+
+/// Salesforce deletes at most 200 records per sObject Collections request.
+const DELETE_BATCH_SIZE: usize = 200;
+
+/// Escape a value for use inside a single-quoted SOQL string literal.
+pub(crate) fn soql_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn soql_in_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("'{}'", soql_escape(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Helpers shared by the tests of the Tooling API and the modules built on it.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use serde_json::{json, Value};
+
+    pub(crate) fn create_test_tooling_api(server_url: &str) -> ToolingApi {
+        let mut client = Client::new();
+        client.set_instance_url(server_url);
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        client.set_version("v60.0");
+        ToolingApi::new(client)
+    }
+
+    /// One `ApexLog` record as returned by a Tooling API query.
+    pub(crate) fn apex_log(id: &str, start_time: &str) -> Value {
+        json!({
+            "Id": id,
+            "LogUserId": "005xx",
+            "LogLength": 100,
+            "Operation": "Api",
+            "Request": "Api",
+            "Status": "Success",
+            "StartTime": start_time,
+            "DurationMilliseconds": 5
+        })
+    }
+
+    /// A single, final page of a Tooling API query.
+    pub(crate) fn query_page(records: Value) -> String {
+        json!({"totalSize": records.as_array().unwrap().len(), "done": true, "records": records})
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{apex_log, create_test_tooling_api, query_page};
+    use super::*;
+    use mockito::Server;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_base_path() {
+        let mut client = Client::new();
+        client.set_instance_url("https://na1.salesforce.com");
+        client.set_version("v60.0");
+        let api = ToolingApi::new(client);
+        assert_eq!(
+            api.base_path().unwrap(),
+            "https://na1.salesforce.com/services/data/v60.0/tooling"
+        );
+    }
+
+    #[test]
+    fn test_base_path_not_logged_in() {
+        let api = ToolingApi::new(Client::new());
+        match api.base_path() {
+            Err(Error::NotLoggedIn) => {}
+            other => panic!("Expected NotLoggedIn, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".into(),
+                "SELECT Id, Name FROM ApexClass".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "size": 1,
+                    "totalSize": 1,
+                    "done": true,
+                    "entityTypeName": "ApexClass",
+                    "records": [{"Id": "01pxx", "Name": "MyClass"}]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res: Value = api.query("SELECT Id, Name FROM ApexClass").await.unwrap();
+        assert_eq!(res["records"][0]["Name"], "MyClass");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_query_more() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/tooling/query/01gxx-2000")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"totalSize": 2001, "done": true, "records": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res: Value = api
+            .query_more("services/data/v60.0/tooling/query/01gxx-2000")
+            .await
+            .unwrap();
+        assert_eq!(res["done"], true);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_find_by_id() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/tooling/sobjects/ApexClass/01pxx",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"Id": "01pxx", "Body": "public class MyClass {}"}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res: Value = api.find_by_id("ApexClass", "01pxx").await.unwrap();
+        assert_eq!(res["Body"], "public class MyClass {}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_update_destroy() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/services/data/v60.0/tooling/sobjects/TraceFlag")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"LogType": "DEVELOPER_LOG"}),
+            ))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "7tfxx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+        let update = server
+            .mock(
+                "PATCH",
+                "/services/data/v60.0/tooling/sobjects/TraceFlag/7tfxx",
+            )
+            .with_status(204)
+            .create_async()
+            .await;
+        let destroy = server
+            .mock(
+                "DELETE",
+                "/services/data/v60.0/tooling/sobjects/TraceFlag/7tfxx",
+            )
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res = api
+            .create("TraceFlag", json!({"LogType": "DEVELOPER_LOG"}))
+            .await
+            .unwrap();
+        assert_eq!(res.id, "7tfxx");
+        assert!(res.created);
+        api.update(
+            "TraceFlag",
+            "7tfxx",
+            json!({"ExpirationDate": "2024-01-02T00:00:00Z"}),
+        )
+        .await
+        .unwrap();
+        api.destroy("TraceFlag", "7tfxx").await.unwrap();
+        create.assert_async().await;
+        update.assert_async().await;
+        destroy.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_destroy_failure() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "DELETE",
+                "/services/data/v60.0/tooling/sobjects/ApexClass/01pxx",
+            )
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"message": "entity is deleted", "errorCode": "ENTITY_IS_DELETED"}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        match api.destroy("ApexClass", "01pxx").await {
+            Err(Error::ErrorResponses(errors)) => {
+                assert_eq!(errors[0].error_code, "ENTITY_IS_DELETED")
+            }
+            other => panic!("Expected ErrorResponses, got {:?}", other),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_describe() {
+        let mut server = Server::new_async().await;
+        let global = server
+            .mock("GET", "/services/data/v60.0/tooling/sobjects")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "encoding": "UTF-8",
+                    "maxBatchSize": 200,
+                    "sobjects": [{"name": "ApexClass", "queryable": true}]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let describe = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/tooling/sobjects/ApexClass/describe",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "name": "ApexClass",
+                    "fields": [{"name": "Body", "type": "textarea"}]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res = api.describe_global().await.unwrap();
+        assert_eq!(res.sobjects[0].name, "ApexClass");
+        let res = api.describe("ApexClass").await.unwrap();
+        assert!(res.field("body").is_some());
+        global.assert_async().await;
+        describe.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_anonymous() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/tooling/executeAnonymous/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "anonymousBody".into(),
+                "System.debug('hi');".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "line": -1,
+                    "column": -1,
+                    "compiled": true,
+                    "success": true,
+                    "compileProblem": null,
+                    "exceptionStackTrace": null,
+                    "exceptionMessage": null
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res = api.execute_anonymous("System.debug('hi');").await.unwrap();
+        assert!(res.compiled);
+        assert!(res.success);
+        assert_eq!(res.line, -1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_query_records_follows_next_records_url() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "totalSize": 2,
+                    "done": false,
+                    "nextRecordsUrl": "/services/data/v60.0/tooling/query/01gxx-1",
+                    "records": [{"Id": "a"}]
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/services/data/v60.0/tooling/query/01gxx-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([{"Id": "b"}])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let records: Vec<Value> = api.query_records("SELECT Id FROM ApexLog").await.unwrap();
+        assert_eq!(records, vec![json!({"Id": "a"}), json!({"Id": "b"})]);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_tests_synchronous() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/data/v60.0/tooling/runTestsSynchronous/")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"tests": [{"className": "AccountTest"}]}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "numTestsRun": 1,
+                    "numFailures": 0,
+                    "totalTime": 35.0,
+                    "successes": [{"id": "01pxx", "name": "AccountTest", "methodName": "testOk", "time": 35.0}],
+                    "failures": [],
+                    "codeCoverage": []
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let res = api
+            .run_tests_synchronous(&RunTestsRequest::classes(&["AccountTest"]))
+            .await
+            .unwrap();
+        assert_eq!(res.num_tests_run, 1);
+        assert!(TestRunSummary::from(res).is_success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_run_tests_polls_until_finished() {
+        use mockito::Matcher;
+
+        let mut server = Server::new_async().await;
+        let run = server
+            .mock("POST", "/services/data/v60.0/tooling/runTestsAsynchronous/")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("\"707xx\"")
+            .create_async()
+            .await;
+        let processing = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexTestQueueItem".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"Id": "709a", "ApexClassId": "01pA", "Status": "Completed"},
+                {"Id": "709b", "ApexClassId": "01pB", "Status": "Processing"}
+            ])))
+            .expect(1)
+            .create_async()
+            .await;
+        let completed = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexTestQueueItem".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"Id": "709a", "ApexClassId": "01pA", "Status": "Completed"},
+                {"Id": "709b", "ApexClassId": "01pB", "Status": "Completed"}
+            ])))
+            .expect(1)
+            .create_async()
+            .await;
+        let results = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("FROM\\+ApexTestResult\\+WHERE".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"ApexClass": {"Name": "ATest"}, "MethodName": "ok", "Outcome": "Pass", "RunTime": 10},
+                {
+                    "ApexClass": {"Name": "BTest"}, "MethodName": "bad", "Outcome": "Fail",
+                    "Message": "System.AssertException", "StackTrace": "Class.BTest.bad: line 3",
+                    "RunTime": 20
+                }
+            ])))
+            .create_async()
+            .await;
+        let per_test_coverage = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("FROM\\+ApexCodeCoverage\\+WHERE".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"ApexClassOrTriggerId": "01pS"},
+                {"ApexClassOrTriggerId": "01pS"}
+            ])))
+            .create_async()
+            .await;
+        let aggregate_coverage = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexCodeCoverageAggregate".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([{
+                "ApexClassOrTrigger": {"Name": "Service"},
+                "NumLinesCovered": 9,
+                "NumLinesUncovered": 1
+            }])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let summary = api
+            .run_tests(
+                &RunTestsRequest::classes(&["ATest", "BTest"]),
+                Duration::from_millis(1),
+                Duration::from_secs(10),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(summary.job_id.as_deref(), Some("707xx"));
+        assert_eq!(summary.passed(), 1);
+        assert_eq!(summary.failed(), 1);
+        let failure = summary.failures().next().unwrap();
+        assert_eq!(failure.class_name, "BTest");
+        assert_eq!(
+            failure.stack_trace.as_deref(),
+            Some("Class.BTest.bad: line 3")
+        );
+        assert_eq!(summary.coverage[0].name, "Service");
+        assert_eq!(summary.coverage_percent(), 90.0);
+
+        run.assert_async().await;
+        processing.assert_async().await;
+        completed.assert_async().await;
+        results.assert_async().await;
+        per_test_coverage.assert_async().await;
+        aggregate_coverage.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_test_run_empty_queue_is_not_finished() {
+        use mockito::Matcher;
+
+        let mut server = Server::new_async().await;
+        let not_queued_yet = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexTestQueueItem".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([])))
+            .expect(1)
+            .create_async()
+            .await;
+        let completed = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexTestQueueItem".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"Id": "709a", "ApexClassId": "01pA", "Status": "Completed"}
+            ])))
+            .expect(1)
+            .create_async()
+            .await;
+        let results = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex(
+                "FROM\\+ApexTestResult\\+WHERE\\+AsyncApexJobId\\+%3D\\+%27707%5C%27xx%27".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"ApexClass": {"Name": "ATest"}, "MethodName": "ok", "Outcome": "Pass", "RunTime": 10}
+            ])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let summary = api
+            .wait_for_test_run(
+                "707'xx",
+                Duration::from_millis(1),
+                Duration::from_secs(10),
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary.job_id.as_deref(), Some("707'xx"));
+        assert_eq!(summary.passed(), 1);
+        not_queued_yet.assert_async().await;
+        completed.assert_async().await;
+        results.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_test_run_deadline() {
+        use mockito::Matcher;
+
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("ApexTestQueueItem".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                {"Id": "709a", "ApexClassId": "01pA", "Status": "Processing"}
+            ])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let result = api
+            .wait_for_test_run(
+                "707xx",
+                Duration::from_millis(5),
+                Duration::from_millis(20),
+                true,
+            )
+            .await;
+        match result {
+            Err(Error::TimeoutError(msg)) => assert!(msg.contains("707xx")),
+            other => panic!("expected TimeoutError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_soql_escape() {
+        assert_eq!(soql_escape("O'Brien"), "O\\'Brien");
+        assert_eq!(soql_escape("a\\b"), "a\\\\b");
+        assert_eq!(
+            soql_in_list(&["01pA".to_string(), "01pB".to_string()]),
+            "'01pA', '01pB'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_debug_level_and_trace_flag() {
+        use crate::requests::trace_flag_request::DebugLevel;
+
+        let mut server = Server::new_async().await;
+        let level = server
+            .mock("POST", "/services/data/v60.0/tooling/sobjects/DebugLevel")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"DeveloperName": "Tail", "ApexCode": "DEBUG"}),
+            ))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "7dlxx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+        let flag = server
+            .mock("POST", "/services/data/v60.0/tooling/sobjects/TraceFlag")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "TracedEntityId": "005xx",
+                "DebugLevelId": "7dlxx",
+                "LogType": "USER_DEBUG"
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"id": "7tfxx", "success": true, "errors": []}).to_string())
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let level_id = api
+            .create_debug_level(&DebugLevel::new("Tail"))
+            .await
+            .unwrap()
+            .id;
+        let res = api
+            .create_trace_flag(&TraceFlag::for_user(
+                "005xx",
+                &level_id,
+                chrono::Duration::hours(1),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.id, "7tfxx");
+        level.assert_async().await;
+        flag.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_logs() {
+        use chrono::TimeZone;
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".into(),
+                "SELECT Id, LogUserId, LogLength, Operation, Request, Status, StartTime, \
+                 DurationMilliseconds, Application, Location FROM ApexLog \
+                 WHERE StartTime >= 2024-01-01T12:00:00Z AND LogUserId = '005xx' \
+                 ORDER BY StartTime ASC, Id ASC"
+                    .into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([apex_log(
+                "07Lxx",
+                "2024-01-01T12:00:01.000+0000"
+            )])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let since = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let logs = api.list_logs(Some(since), Some("005xx")).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].id, "07Lxx");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_log_body() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/tooling/sobjects/ApexLog/07Lxx/Body",
+            )
+            .with_status(200)
+            .with_header("content-type", "text/plain")
+            .with_body("60.0 APEX_CODE,DEBUG\n12:00:00.1 (100)|USER_DEBUG|[1]|DEBUG|hi\n")
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let body = api.log_body("07Lxx").await.unwrap();
+        assert!(body.contains("USER_DEBUG"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_logs_in_batches() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("DELETE", "/services/data/v60.0/composite/sobjects")
+            .match_query(mockito::Matcher::Regex(
+                "^ids=id0,.*,id199&allOrNone=false$".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                Value::Array(
+                    (0..200)
+                        .map(|i| json!({"id": format!("id{}", i), "success": true, "errors": []}))
+                        .collect(),
+                )
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let second = server
+            .mock("DELETE", "/services/data/v60.0/composite/sobjects")
+            .match_query(mockito::Matcher::Regex(
+                "^ids=id200&allOrNone=false$".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{
+                    "id": "id200",
+                    "success": false,
+                    "errors": [{"statusCode": "ENTITY_IS_DELETED", "message": "deleted"}]
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let ids: Vec<String> = (0..201).map(|i| format!("id{}", i)).collect();
+        let results = api.delete_logs(&ids).await.unwrap();
+        assert_eq!(results.len(), 201);
+        assert!(!results[200].success);
+        assert_eq!(results[200].errors[0].error_code, "ENTITY_IS_DELETED");
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_logs_before() {
+        use chrono::TimeZone;
+
+        let mut server = Server::new_async().await;
+        let query = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".into(),
+                "SELECT Id FROM ApexLog WHERE StartTime < 2024-01-01T00:00:00Z".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([{"Id": "07La"}, {"Id": "07Lb"}])))
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/services/data/v60.0/composite/sobjects")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "ids".into(),
+                "07La,07Lb".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([
+                    {"id": "07La", "success": true, "errors": []},
+                    {"id": "07Lb", "success": true, "errors": []}
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let before = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let results = api.delete_logs_before(before).await.unwrap();
+        assert_eq!(results.len(), 2);
+        query.assert_async().await;
+        delete.assert_async().await;
+    }
+}
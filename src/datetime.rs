@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+
+/// Salesforce renders datetimes as `2024-01-01T00:00:00.000+0000`, which is not RFC 3339.
+const SALESFORCE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%z";
+
+pub(crate) fn parse_datetime(value: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_str(value, SALESFORCE_DATETIME_FORMAT)
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+pub(crate) fn format_datetime(value: &DateTime<Utc>) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string()
+}
+
+/// SOQL datetime literal, e.g. `2024-01-01T00:00:00Z`. Not quoted in queries.
+pub(crate) fn format_soql_datetime(value: &DateTime<Utc>) -> String {
+    value.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// `#[serde(with = "...")]` adapter for Salesforce datetime strings.
+pub(crate) mod salesforce_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_datetime(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse_datetime(&value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_salesforce_format() {
+        let dt = parse_datetime("2013-05-08T21:20:00.000+0000").unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2013, 5, 8, 21, 20, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_offset_and_rfc3339() {
+        let dt = parse_datetime("2013-05-08T14:20:00.000-0700").unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2013, 5, 8, 21, 20, 0).unwrap());
+        let dt = parse_datetime("2013-05-08T21:20:00Z").unwrap();
+        assert_eq!(dt, Utc.with_ymd_and_hms(2013, 5, 8, 21, 20, 0).unwrap());
+    }
+
+    #[test]
+    fn test_format_soql_datetime() {
+        let dt = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(format_soql_datetime(&dt), "2024-01-02T03:04:05Z");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_datetime("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        let dt = Utc.with_ymd_and_hms(2013, 5, 8, 21, 20, 0).unwrap();
+        assert_eq!(format_datetime(&dt), "2013-05-08T21:20:00.000+0000");
+    }
+}
@@ -0,0 +1,346 @@
+use crate::client::tooling_api::ToolingApi;
+use crate::errors::Error;
+use crate::responses::apex_log::ApexLog;
+use chrono::{DateTime, NaiveTime, Utc};
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Event type of a debug log line, e.g. `USER_DEBUG` or `SOQL_EXECUTE_BEGIN`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.apexcode.meta/apexcode/apex_debugging_debug_log.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEventType {
+    ExecutionStarted,
+    ExecutionFinished,
+    CodeUnitStarted,
+    CodeUnitFinished,
+    MethodEntry,
+    MethodExit,
+    UserDebug,
+    SoqlExecuteBegin,
+    SoqlExecuteEnd,
+    DmlBegin,
+    DmlEnd,
+    CalloutRequest,
+    CalloutResponse,
+    ExceptionThrown,
+    FatalError,
+    LimitUsageForNs,
+    /// Any event type without a dedicated variant, as written in the log.
+    Other(String),
+}
+
+impl LogEventType {
+    pub fn parse(value: &str) -> LogEventType {
+        match value {
+            "EXECUTION_STARTED" => LogEventType::ExecutionStarted,
+            "EXECUTION_FINISHED" => LogEventType::ExecutionFinished,
+            "CODE_UNIT_STARTED" => LogEventType::CodeUnitStarted,
+            "CODE_UNIT_FINISHED" => LogEventType::CodeUnitFinished,
+            "METHOD_ENTRY" => LogEventType::MethodEntry,
+            "METHOD_EXIT" => LogEventType::MethodExit,
+            "USER_DEBUG" => LogEventType::UserDebug,
+            "SOQL_EXECUTE_BEGIN" => LogEventType::SoqlExecuteBegin,
+            "SOQL_EXECUTE_END" => LogEventType::SoqlExecuteEnd,
+            "DML_BEGIN" => LogEventType::DmlBegin,
+            "DML_END" => LogEventType::DmlEnd,
+            "CALLOUT_REQUEST" => LogEventType::CalloutRequest,
+            "CALLOUT_RESPONSE" => LogEventType::CalloutResponse,
+            "EXCEPTION_THROWN" => LogEventType::ExceptionThrown,
+            "FATAL_ERROR" => LogEventType::FatalError,
+            "LIMIT_USAGE_FOR_NS" => LogEventType::LimitUsageForNs,
+            other => LogEventType::Other(other.to_string()),
+        }
+    }
+}
+
+/// One event of a debug log, e.g. `12:00:00.123 (4567)|USER_DEBUG|[3]|DEBUG|hello`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEvent {
+    /// Wall-clock time of the event in the org's time zone.
+    pub timestamp: NaiveTime,
+    /// Nanoseconds since the start of the request.
+    pub elapsed_nanos: Option<u64>,
+    pub event_type: LogEventType,
+    /// The remaining `|`-separated fields. Continuation lines, such as multi-line
+    /// `System.debug` output, are appended to the last field.
+    pub details: Vec<String>,
+}
+
+impl LogEvent {
+    fn parse_line(line: &str) -> Option<LogEvent> {
+        let mut fields = line.split('|');
+        let (time, elapsed) = match fields.next()?.split_once(' ') {
+            Some((time, elapsed)) => (time, Some(elapsed)),
+            None => return None,
+        };
+        let timestamp = NaiveTime::parse_from_str(time, "%H:%M:%S%.f").ok()?;
+        let elapsed_nanos = elapsed
+            .and_then(|e| e.trim().strip_prefix('('))
+            .and_then(|e| e.strip_suffix(')'))
+            .and_then(|e| e.parse().ok());
+        let event_type = LogEventType::parse(fields.next()?);
+        Some(LogEvent {
+            timestamp,
+            elapsed_nanos,
+            event_type,
+            details: fields.map(|f| f.to_string()).collect(),
+        })
+    }
+}
+
+/// Split a debug log body into events. The header line with the log levels is skipped.
+pub fn parse_log(body: &str) -> Vec<LogEvent> {
+    let mut events: Vec<LogEvent> = vec![];
+    for line in body.lines() {
+        match LogEvent::parse_line(line) {
+            Some(event) => events.push(event),
+            None => {
+                if let Some(last) = events.last_mut() {
+                    match last.details.last_mut() {
+                        Some(detail) => {
+                            detail.push('\n');
+                            detail.push_str(line);
+                        }
+                        None => last.details.push(line.to_string()),
+                    }
+                }
+            }
+        }
+    }
+    events
+}
+
+/// A debug log with its downloaded body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugLog {
+    pub log: ApexLog,
+    pub body: String,
+}
+
+impl DebugLog {
+    pub fn events(&self) -> Vec<LogEvent> {
+        parse_log(&self.body)
+    }
+}
+
+/// Cursor over new debug logs, for following logs as they are written.
+///
+/// Serializable, so a tail can resume where it stopped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DebugLogTail {
+    user_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    /// Logs already returned that started at `since`; SOQL filters at second precision.
+    seen_ids: Vec<String>,
+}
+
+impl DebugLogTail {
+    /// Follow logs that start at or after `since`, or every existing log when `None`.
+    pub fn new(since: Option<DateTime<Utc>>) -> Self {
+        DebugLogTail {
+            since,
+            ..Default::default()
+        }
+    }
+
+    /// Only follow the logs of one user.
+    pub fn for_user(mut self, user_id: &str) -> Self {
+        self.user_id = Some(user_id.to_string());
+        self
+    }
+
+    pub fn since(&self) -> Option<DateTime<Utc>> {
+        self.since
+    }
+
+    /// Logs written since the previous poll, oldest first.
+    pub async fn poll(&mut self, api: &mut ToolingApi) -> Result<Vec<ApexLog>, Error> {
+        let logs = api.list_logs(self.since, self.user_id.as_deref()).await?;
+        let new_logs: Vec<ApexLog> = logs
+            .into_iter()
+            .filter(|log| !self.seen_ids.contains(&log.id))
+            .collect();
+
+        if let Some(latest) = new_logs.iter().map(|log| log.start_time).max() {
+            if Some(latest) != self.since {
+                self.seen_ids.clear();
+                self.since = Some(latest);
+            }
+            self.seen_ids.extend(
+                new_logs
+                    .iter()
+                    .filter(|log| log.start_time == latest)
+                    .map(|log| log.id.clone()),
+            );
+        }
+        Ok(new_logs)
+    }
+
+    /// Poll every `poll_interval` and yield each new log with its body. The stream never ends;
+    /// errors are yielded and polling carries on.
+    pub fn into_stream(
+        self,
+        api: ToolingApi,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<DebugLog, Error>> {
+        let state = (self, api, VecDeque::<ApexLog>::new(), false);
+        stream::unfold(
+            state,
+            move |(mut tail, mut api, mut pending, mut polled)| async move {
+                loop {
+                    if let Some(log) = pending.pop_front() {
+                        let item = api
+                            .log_body(&log.id)
+                            .await
+                            .map(|body| DebugLog { log, body });
+                        return Some((item, (tail, api, pending, polled)));
+                    }
+                    if polled {
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                    polled = true;
+                    match tail.poll(&mut api).await {
+                        Ok(logs) => pending.extend(logs),
+                        Err(e) => return Some((Err(e), (tail, api, pending, polled))),
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tooling_api::test_support::{apex_log, create_test_tooling_api, query_page};
+    use chrono::TimeZone;
+    use futures_util::StreamExt;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    const LOG: &str = "60.0 APEX_CODE,DEBUG;APEX_PROFILING,INFO\n\
+        Execute Anonymous: System.debug('hi');\n\
+        12:00:00.0 (171234)|USER_INFO|[EXTERNAL]|005xx|user@example.com\n\
+        12:00:00.123 (4567890)|USER_DEBUG|[1]|DEBUG|first line\n\
+        second line\n\
+        12:00:00.200 (5000000)|SOQL_EXECUTE_BEGIN|[2]|Aggregations:0|SELECT Id FROM Account\n\
+        12:00:00.210 (6000000)|EXCEPTION_THROWN|[3]|System.NullPointerException: boom\n";
+
+    #[test]
+    fn test_parse_log() {
+        let events = parse_log(LOG);
+        assert_eq!(events.len(), 4);
+
+        assert_eq!(
+            events[0].event_type,
+            LogEventType::Other("USER_INFO".to_string())
+        );
+        assert_eq!(events[0].elapsed_nanos, Some(171234));
+
+        let debug = &events[1];
+        assert_eq!(debug.event_type, LogEventType::UserDebug);
+        assert_eq!(
+            debug.timestamp,
+            NaiveTime::from_hms_milli_opt(12, 0, 0, 123).unwrap()
+        );
+        assert_eq!(
+            debug.details,
+            vec!["[1]", "DEBUG", "first line\nsecond line"]
+        );
+
+        assert_eq!(events[2].event_type, LogEventType::SoqlExecuteBegin);
+        assert_eq!(events[2].details[2], "SELECT Id FROM Account");
+        assert_eq!(events[3].event_type, LogEventType::ExceptionThrown);
+    }
+
+    #[test]
+    fn test_parse_empty_log() {
+        assert!(parse_log("").is_empty());
+        assert!(parse_log("60.0 APEX_CODE,DEBUG\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_poll_skips_logs_already_seen() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("^q=[^&]*ApexLog\\+ORDER".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                apex_log("07La", "2024-01-01T12:00:00.000+0000"),
+                apex_log("07Lb", "2024-01-01T12:00:05.000+0000")
+            ])))
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex(
+                "StartTime\\+%3E%3D\\+2024-01-01T12%3A00%3A05Z".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([
+                apex_log("07Lb", "2024-01-01T12:00:05.000+0000"),
+                apex_log("07Lc", "2024-01-01T12:00:09.000+0000")
+            ])))
+            .create_async()
+            .await;
+
+        let mut api = create_test_tooling_api(&server.url());
+        let mut tail = DebugLogTail::new(None);
+        let logs = tail.poll(&mut api).await.unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(
+            tail.since(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 5).unwrap())
+        );
+
+        let logs = tail.poll(&mut api).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].id, "07Lc");
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_into_stream() {
+        let mut server = Server::new_async().await;
+        let list = server
+            .mock("GET", "/services/data/v60.0/tooling/query/")
+            .match_query(Matcher::Regex("LogUserId\\+%3D\\+%27005xx%27".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(query_page(json!([apex_log(
+                "07La",
+                "2024-01-01T12:00:00.000+0000"
+            )])))
+            .create_async()
+            .await;
+        let body = server
+            .mock(
+                "GET",
+                "/services/data/v60.0/tooling/sobjects/ApexLog/07La/Body",
+            )
+            .with_status(200)
+            .with_body(LOG)
+            .create_async()
+            .await;
+
+        let api = create_test_tooling_api(&server.url());
+        let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let stream = DebugLogTail::new(Some(since))
+            .for_user("005xx")
+            .into_stream(api, Duration::from_millis(1));
+        let mut stream = Box::pin(stream);
+
+        let log = stream.next().await.unwrap().unwrap();
+        assert_eq!(log.log.id, "07La");
+        assert_eq!(log.events()[1].event_type, LogEventType::UserDebug);
+        list.assert_async().await;
+        body.assert_async().await;
+    }
+}
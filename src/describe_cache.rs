@@ -0,0 +1,284 @@
+use crate::responses::describe_sobject_result::DescribeSObjectResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of every file the cache writes, so `clear` leaves other files in the directory alone.
+const FILE_PREFIX: &str = "describe-";
+const FILE_SUFFIX: &str = ".json";
+
+/// Identifies one cached describe: the same object can differ between orgs and API versions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescribeCacheKey {
+    pub org_id: String,
+    pub version: String,
+    pub sobject: String,
+}
+
+impl DescribeCacheKey {
+    pub fn new(org_id: &str, version: &str, sobject: &str) -> Self {
+        DescribeCacheKey {
+            org_id: org_id.to_string(),
+            version: version.to_string(),
+            // sObject names are case-insensitive
+            sobject: sobject.to_ascii_lowercase(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        let sanitize = |s: &str| -> String {
+            s.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '.' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        };
+        format!(
+            "{}{}-{}-{}{}",
+            FILE_PREFIX,
+            sanitize(&self.org_id),
+            sanitize(&self.version),
+            sanitize(&self.sobject),
+            FILE_SUFFIX
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedDescribe {
+    /// Value sent back as `If-Modified-Since` when revalidating.
+    pub last_modified: String,
+    pub describe: DescribeSObjectResult,
+}
+
+/// Opt-in cache for `RestApi::describe`.
+///
+/// Entries are revalidated with `If-Modified-Since` on every describe, so a
+/// `304 Not Modified` answer reuses the cached copy instead of downloading it again.
+/// With a directory configured, entries are also written as JSON files and survive
+/// process restarts. Disk errors are logged and otherwise ignored.
+#[derive(Debug, Clone, Default)]
+pub struct DescribeCache {
+    entries: HashMap<DescribeCacheKey, CachedDescribe>,
+    directory: Option<PathBuf>,
+}
+
+impl DescribeCache {
+    pub fn new() -> Self {
+        DescribeCache::default()
+    }
+
+    pub fn with_directory<P: AsRef<Path>>(directory: P) -> Self {
+        DescribeCache {
+            entries: HashMap::new(),
+            directory: Some(directory.as_ref().to_path_buf()),
+        }
+    }
+
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up an entry in memory, falling back to the on-disk copy.
+    pub fn get(&mut self, key: &DescribeCacheKey) -> Option<&CachedDescribe> {
+        if !self.entries.contains_key(key) {
+            let entry = self.read_from_disk(key)?;
+            self.entries.insert(key.clone(), entry);
+        }
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: DescribeCacheKey, entry: CachedDescribe) {
+        if let Some(path) = self.path_for(&key) {
+            let written =
+                fs::create_dir_all(path.parent().unwrap_or(Path::new("."))).and_then(|_| {
+                    let json = serde_json::to_vec(&entry)?;
+                    fs::write(&path, json)
+                });
+            if let Err(e) = written {
+                log::warn!("Could not write describe cache {}: {}", path.display(), e);
+            }
+        }
+        self.entries.insert(key, entry);
+    }
+
+    pub fn invalidate(&mut self, key: &DescribeCacheKey) {
+        self.entries.remove(key);
+        if let Some(path) = self.path_for(key) {
+            if path.exists() {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::warn!("Could not remove describe cache {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    /// Drop every in-memory entry and every `describe-*.json` file in the directory,
+    /// including files written by earlier processes. Other files are left alone.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        let dir = match &self.directory {
+            Some(dir) => dir,
+            None => return,
+        };
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Could not list describe cache {}: {}", dir.display(), e);
+                }
+                return;
+            }
+        };
+        for path in read_dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            let is_cache_file = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| {
+                    name.starts_with(FILE_PREFIX) && name.ends_with(FILE_SUFFIX)
+                });
+            if path.is_file() && is_cache_file {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::warn!("Could not remove describe cache {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    fn path_for(&self, key: &DescribeCacheKey) -> Option<PathBuf> {
+        self.directory.as_ref().map(|dir| dir.join(key.file_name()))
+    }
+
+    fn read_from_disk(&self, key: &DescribeCacheKey) -> Option<CachedDescribe> {
+        let path = self.path_for(key)?;
+        let bytes = fs::read(&path).ok()?;
+        match serde_json::from_slice(&bytes) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!(
+                    "Ignoring unreadable describe cache {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> CachedDescribe {
+        CachedDescribe {
+            last_modified: "Mon, 01 Jan 2024 00:00:00 GMT".to_string(),
+            describe: DescribeSObjectResult {
+                name: name.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rustforce-describe-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_key_is_case_insensitive_on_sobject() {
+        assert_eq!(
+            DescribeCacheKey::new("00D", "v60.0", "Account"),
+            DescribeCacheKey::new("00D", "v60.0", "account")
+        );
+        assert_ne!(
+            DescribeCacheKey::new("00D", "v60.0", "Account"),
+            DescribeCacheKey::new("00D", "v59.0", "Account")
+        );
+    }
+
+    #[test]
+    fn test_file_name_is_sanitized() {
+        let key = DescribeCacheKey::new("https://na1.salesforce.com", "v60.0", "My__c");
+        assert_eq!(
+            key.file_name(),
+            "describe-https___na1.salesforce.com-v60.0-my__c.json"
+        );
+    }
+
+    #[test]
+    fn test_in_memory_insert_get_invalidate() {
+        let mut cache = DescribeCache::new();
+        let key = DescribeCacheKey::new("00D", "v60.0", "Account");
+        assert!(cache.get(&key).is_none());
+
+        cache.insert(key.clone(), entry("Account"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&key).unwrap().describe.name, "Account");
+
+        cache.invalidate(&key);
+        assert!(cache.is_empty());
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_disk_round_trip() {
+        let dir = temp_dir("round-trip");
+        let key = DescribeCacheKey::new("00D", "v60.0", "Contact");
+
+        let mut cache = DescribeCache::with_directory(&dir);
+        cache.insert(key.clone(), entry("Contact"));
+        assert!(dir.join(key.file_name()).exists());
+
+        let mut reloaded = DescribeCache::with_directory(&dir);
+        assert_eq!(reloaded.get(&key).unwrap().describe.name, "Contact");
+
+        reloaded.clear();
+        assert!(!dir.join(key.file_name()).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clear_removes_files_not_loaded() {
+        let dir = temp_dir("clear");
+        let loaded = DescribeCacheKey::new("00D", "v60.0", "Account");
+        let on_disk_only = DescribeCacheKey::new("00D", "v59.0", "Account");
+
+        let mut writer = DescribeCache::with_directory(&dir);
+        writer.insert(loaded.clone(), entry("Account"));
+        writer.insert(on_disk_only.clone(), entry("Account"));
+        fs::write(dir.join("settings.json"), "{}").unwrap();
+        fs::write(dir.join("notes.txt"), "keep me").unwrap();
+
+        let mut cache = DescribeCache::with_directory(&dir);
+        assert!(cache.get(&loaded).is_some());
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(!dir.join(loaded.file_name()).exists());
+        assert!(!dir.join(on_disk_only.file_name()).exists());
+        assert!(dir.join("settings.json").exists());
+        assert!(dir.join("notes.txt").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
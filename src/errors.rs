@@ -1,5 +1,8 @@
 use std::fmt;
 use crate::responses::error_response::ErrorResponse;
+use crate::responses::metadata_result::MetadataSaveResult;
+use crate::responses::publish_result::PublishResult;
+use crate::responses::soap_fault::SoapFault;
 use crate::responses::token_error_response::TokenErrorResponse;
 use reqwest::header::InvalidHeaderValue;
 
@@ -12,9 +15,29 @@ pub enum Error {
     HttpError(reqwest::Error),
     HeaderError(InvalidHeaderValue),
     DeserializeError(serde_json::Error),
+    IoError(std::io::Error),
     ErrorResponses(Vec<ErrorResponse>),
     DescribeError(ErrorResponse),
     LoginError(ErrorResponse),
+    /// An upsert matched more than one record by external ID. Holds the URLs of the matching records.
+    MultipleChoices(Vec<String>),
+    /// A replication window was empty, reversed, or reached back further than Salesforce keeps changes.
+    DateRangeError(String),
+    /// A SOAP response was not well-formed or lacked an expected element.
+    XmlError(String),
+    SoapFault(SoapFault),
+    /// Salesforce rejected a platform event, e.g. with `PLATFORM_EVENT_PUBLISH_FAILED`.
+    PublishError(Vec<ErrorResponse>),
+    /// A Bayeux handshake, subscribe or connect was refused, e.g. `403::Handshake denied`.
+    StreamingError(String),
+    /// A polled job did not finish within the allowed time.
+    TimeoutError(String),
+    /// A chunked Metadata API call failed after earlier chunks were applied. Holds the results
+    /// of those chunks and the error of the failing one.
+    MetadataPartialFailure(Vec<MetadataSaveResult>, Box<Error>),
+    /// A chunked `publish_events` call failed after earlier chunks were published. Holds the
+    /// results of those chunks and the error of the failing one.
+    PublishPartialFailure(Vec<PublishResult>, Box<Error>),
 }
 
 impl std::error::Error for Error {
@@ -23,6 +46,9 @@ impl std::error::Error for Error {
             Error::HttpError(e) => Some(e),
             Error::HeaderError(e) => Some(e),
             Error::DeserializeError(e) => Some(e),
+            Error::IoError(e) => Some(e),
+            Error::MetadataPartialFailure(_, e) => Some(e.as_ref()),
+            Error::PublishPartialFailure(_, e) => Some(e.as_ref()),
             _ => None,
         }
     }
@@ -37,9 +63,33 @@ impl fmt::Display for Error {
             Error::HttpError(e) => write!(f, "HTTP request to Salesforce failed: {}", e),
             Error::HeaderError(e) => write!(f, "Invalid header value: {}", e),
             Error::DeserializeError(e) => write!(f, "Could not deserialize response: {}", e),
+            Error::IoError(e) => write!(f, "I/O error: {}", e),
             Error::ErrorResponses(resp) => write!(f, "Error response from Salesforce {:?}", resp),
             Error::DescribeError(resp) => write!(f, "Error completing describe {:?}", resp),
             Error::LoginError(resp) => write!(f, "Error logging in {:?}", resp),
+            Error::MultipleChoices(urls) => {
+                write!(f, "External ID matched multiple records {:?}", urls)
+            }
+            Error::DateRangeError(msg) => write!(f, "Invalid date range: {}", msg),
+            Error::XmlError(msg) => write!(f, "Could not parse XML response: {}", msg),
+            Error::SoapFault(fault) => {
+                write!(f, "SOAP fault {}: {}", fault.fault_code, fault.fault_string)
+            }
+            Error::PublishError(resp) => write!(f, "Could not publish platform event {:?}", resp),
+            Error::StreamingError(msg) => write!(f, "Streaming API error: {}", msg),
+            Error::TimeoutError(msg) => write!(f, "Timed out: {}", msg),
+            Error::MetadataPartialFailure(results, e) => write!(
+                f,
+                "Metadata call failed after {} components were processed: {}",
+                results.len(),
+                e
+            ),
+            Error::PublishPartialFailure(results, e) => write!(
+                f,
+                "Publishing failed after {} events were processed: {}",
+                results.len(),
+                e
+            ),
         }
     }
 }
@@ -62,6 +112,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IoError(e)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +158,36 @@ mod tests {
         assert!(display.starts_with("Could not deserialize response: "));
     }
 
+    #[test]
+    fn test_display_xml_error() {
+        let err = Error::XmlError("missing result".to_string());
+        assert_eq!(
+            format!("{}", err),
+            "Could not parse XML response: missing result"
+        );
+    }
+
+    #[test]
+    fn test_display_soap_fault() {
+        let err = Error::SoapFault(SoapFault {
+            fault_code: "sf:INVALID_SESSION_ID".to_string(),
+            fault_string: "Invalid Session ID".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(
+            format!("{}", err),
+            "SOAP fault sf:INVALID_SESSION_ID: Invalid Session ID"
+        );
+    }
+
+    #[test]
+    fn test_display_io_error() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "pipe closed");
+        let err: Error = io_err.into();
+        assert_eq!(format!("{}", err), "I/O error: pipe closed");
+        assert!(StdError::source(&err).is_some());
+    }
+
     #[test]
     fn test_display_header_error() {
         let header_err = reqwest::header::HeaderValue::from_str("\0").unwrap_err();
@@ -123,6 +209,63 @@ mod tests {
         assert!(display.contains("NOT_FOUND"));
     }
 
+    #[test]
+    fn test_display_publish_error() {
+        let err = Error::PublishError(vec![ErrorResponse {
+            message: "Publishing failed".to_string(),
+            error_code: "PLATFORM_EVENT_PUBLISH_FAILED".to_string(),
+            fields: None,
+        }]);
+        let display = format!("{}", err);
+        assert!(display.contains("Could not publish platform event"));
+        assert!(display.contains("PLATFORM_EVENT_PUBLISH_FAILED"));
+    }
+
+    #[test]
+    fn test_display_streaming_error() {
+        let err = Error::StreamingError("403::Handshake denied".to_string());
+        assert_eq!(
+            format!("{}", err),
+            "Streaming API error: 403::Handshake denied"
+        );
+    }
+
+    #[test]
+    fn test_display_timeout_error() {
+        let err = Error::TimeoutError("test run 707xx did not finish within 1s".to_string());
+        assert_eq!(
+            format!("{}", err),
+            "Timed out: test run 707xx did not finish within 1s"
+        );
+    }
+
+    #[test]
+    fn test_display_metadata_partial_failure() {
+        let err = Error::MetadataPartialFailure(
+            vec![MetadataSaveResult::default()],
+            Box::new(Error::XmlError("no result".to_string())),
+        );
+        assert_eq!(
+            format!("{}", err),
+            "Metadata call failed after 1 components were processed: \
+             Could not parse XML response: no result"
+        );
+        assert!(StdError::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_display_publish_partial_failure() {
+        let err = Error::PublishPartialFailure(
+            vec![PublishResult::default(), PublishResult::default()],
+            Box::new(Error::NotLoggedIn),
+        );
+        assert_eq!(
+            format!("{}", err),
+            "Publishing failed after 2 events were processed: Not logged in"
+        );
+        assert!(StdError::source(&err).is_some());
+    }
+
     #[test]
     fn test_display_describe_error() {
         let resp = ErrorResponse {
@@ -147,6 +290,26 @@ mod tests {
         assert!(display.contains("Error logging in"));
     }
 
+    #[test]
+    fn test_display_multiple_choices() {
+        let err = Error::MultipleChoices(vec![
+            "/services/data/v60.0/sobjects/Account/001xx1".to_string(),
+            "/services/data/v60.0/sobjects/Account/001xx2".to_string(),
+        ]);
+        let display = format!("{}", err);
+        assert!(display.contains("matched multiple records"));
+        assert!(display.contains("001xx2"));
+    }
+
+    #[test]
+    fn test_display_date_range_error() {
+        let err = Error::DateRangeError("start must be before end".to_string());
+        assert_eq!(
+            format!("{}", err),
+            "Invalid date range: start must be before end"
+        );
+    }
+
     #[test]
     fn test_error_is_std_error() {
         let err = Error::NotLoggedIn;
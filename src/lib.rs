@@ -1,12 +1,72 @@
 pub mod access_token;
+pub mod apex_tests;
+pub mod api_usage;
+pub mod change_data_capture;
 pub mod client;
+pub mod debug_logs;
+pub mod describe_cache;
 pub mod errors;
+pub mod package_manifest;
+pub mod replication;
+pub mod requests;
 pub mod responses;
+pub mod streaming;
 
+pub(crate) mod datetime;
 pub(crate) mod xml;
 
+pub use apex_tests::{ClassCoverage, TestMethodResult, TestOutcome, TestRunSummary};
+pub use api_usage::{ApiUsage, ApiUsageThreshold};
+pub use change_data_capture::{
+    change_event_channel, ChangeEvent, ChangeEventHeader, ChangeOutcome, ChangeType,
+};
+pub use client::apex_rest_api::ApexRestApi;
 pub use client::client::Client;
+pub use client::metadata_api::MetadataApi;
 pub use client::rest_api::RestApi;
+pub use client::soap_api::SoapApi;
+pub use client::tooling_api::ToolingApi;
 pub use client::bulk_api::BulkApi;
 pub use client::bulk_api_v2::BulkApiV2;
+pub use debug_logs::{DebugLog, DebugLogTail, LogEvent, LogEventType};
+pub use describe_cache::DescribeCache;
 pub use errors::Error;
+pub use package_manifest::{ManifestDiff, PackageManifest, PackageTypeMembers};
+pub use replication::{ReplicationChanges, ReplicationCursor};
+pub use requests::composite_batch_request::{BatchMethod, BatchSubrequest};
+pub use requests::content_version_request::{ContentVersionMetadata, ShareType};
+pub use requests::deploy_options::{DeployOptions, DeployTestLevel};
+pub use requests::list_metadata_query::ListMetadataQuery;
+pub use requests::partner_request::{LeadConvert, MergeRequest, ProcessAction, ProcessRequest};
+pub use requests::request_options::{AutoAssign, DuplicateRuleHeader, MruUpdate, RequestOptions};
+pub use requests::retrieve_request::RetrieveRequest;
+pub use requests::run_tests_request::{RunTestsRequest, TestItem, TestLevel};
+pub use requests::trace_flag_request::{DebugLevel, LogLevel, LogType, TraceFlag};
+pub use responses::action_result::{ActionDescribe, ActionParameter, ActionResult, ActionSummary};
+pub use responses::apex_log::ApexLog;
+pub use responses::apex_test_response::{
+    CodeCoverageResult, RunTestFailure, RunTestSuccess, RunTestsResult, TestQueueStatus,
+};
+pub use responses::blob_download::BlobDownload;
+pub use responses::composite_batch_response::{BatchResult, CompositeBatchResponse};
+pub use responses::describe_global_result::{
+    DescribeGlobalResponse, DescribeGlobalResult, DescribeGlobalSObjectResult,
+};
+pub use responses::describe_sobject_result::{
+    ChildRelationship, DescribeResponse, DescribeSObjectResult, Field, FieldType, PicklistValue,
+    RecordTypeInfo,
+};
+pub use responses::execute_anonymous_result::ExecuteAnonymousResult;
+pub use responses::explain_response::{ExplainPlan, ExplainResult, LeadingOperationType, PlanNote};
+pub use responses::limits_response::{Limit, OrgLimits, RecordCount};
+pub use responses::metadata_result::{
+    AsyncResult, DeployMessage, DeployResult, DeployStatus, FileProperties, MetadataSaveResult,
+    RetrieveMessage, RetrieveResult, RetrieveStatus,
+};
+pub use responses::partner_result::{LeadConvertResult, MergeResult, ProcessResult, UserInfo};
+pub use responses::publish_result::PublishResult;
+pub use responses::query_result::QueryResult;
+pub use responses::replication_response::{DeletedRecord, GetDeletedResult, GetUpdatedResult};
+pub use responses::save_result::{CreateResponse, SaveResult};
+pub use responses::soap_fault::SoapFault;
+pub use streaming::{ReplayFrom, StreamEvent, StreamingClient};
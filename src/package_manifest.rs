@@ -0,0 +1,588 @@
+use crate::errors::Error;
+use crate::responses::metadata_result::FileProperties;
+use crate::xml::{child_string, children_named, escape_xml, XmlWriter};
+use roxmltree::Document;
+use std::fs;
+use std::path::Path;
+
+const METADATA_NAMESPACE: &str = "http://soap.sforce.com/2006/04/metadata";
+
+/// Source directories and the metadata type of the files they hold, with each file suffix.
+const DIRECTORY_TYPES: &[(&str, &str, &str)] = &[
+    ("applications", "CustomApplication", "app"),
+    ("classes", "ApexClass", "cls"),
+    ("components", "ApexComponent", "component"),
+    ("customMetadata", "CustomMetadata", "md"),
+    ("flexipages", "FlexiPage", "flexipage"),
+    ("flows", "Flow", "flow"),
+    ("globalValueSets", "GlobalValueSet", "globalValueSet"),
+    ("labels", "CustomLabels", "labels"),
+    ("layouts", "Layout", "layout"),
+    ("objects", "CustomObject", "object"),
+    ("pages", "ApexPage", "page"),
+    ("permissionsets", "PermissionSet", "permissionset"),
+    ("profiles", "Profile", "profile"),
+    ("staticresources", "StaticResource", "resource"),
+    ("tabs", "CustomTab", "tab"),
+    ("triggers", "ApexTrigger", "trigger"),
+    ("workflows", "Workflow", "workflow"),
+];
+
+/// Directories holding one sub-directory per component.
+const BUNDLE_TYPES: &[(&str, &str)] = &[
+    ("aura", "AuraDefinitionBundle"),
+    ("lwc", "LightningComponentBundle"),
+];
+
+/// Directories holding one sub-directory per folder.
+const FOLDER_TYPES: &[(&str, &str)] = &[
+    ("dashboards", "Dashboard"),
+    ("email", "EmailTemplate"),
+    ("reports", "Report"),
+];
+
+/// Child components stored under `objects/<Object>/` in source format.
+const OBJECT_CHILD_TYPES: &[(&str, &str)] = &[
+    ("businessProcesses", "BusinessProcess"),
+    ("compactLayouts", "CompactLayout"),
+    ("fieldSets", "FieldSet"),
+    ("fields", "CustomField"),
+    ("listViews", "ListView"),
+    ("recordTypes", "RecordType"),
+    ("validationRules", "ValidationRule"),
+    ("webLinks", "WebLink"),
+];
+
+/// Metadata components by type, as listed in a `package.xml`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/manifest_samples.htm>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageManifest {
+    pub types: Vec<PackageTypeMembers>,
+    /// Metadata API version, e.g. `60.0`.
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageTypeMembers {
+    /// Metadata type, e.g. `CustomObject` or `ApexClass`.
+    pub name: String,
+    /// Component full names; `*` selects every component of the type.
+    pub members: Vec<String>,
+}
+
+/// Components added and removed between two manifests.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    pub added: PackageManifest,
+    /// Suitable as the contents of a `destructiveChanges.xml`.
+    pub removed: PackageManifest,
+}
+
+impl PackageManifest {
+    pub fn new(version: &str) -> Self {
+        PackageManifest {
+            types: vec![],
+            version: Some(version.to_string()),
+        }
+    }
+
+    /// Parse the contents of a `package.xml`.
+    pub fn parse(xml: &str) -> Result<Self, Error> {
+        let doc = Document::parse(xml).map_err(|e| Error::XmlError(e.to_string()))?;
+        let root = doc.root_element();
+        if !root.has_tag_name("Package") {
+            return Err(Error::XmlError(format!(
+                "expected a Package element, found {}",
+                root.tag_name().name()
+            )));
+        }
+
+        let mut manifest = PackageManifest {
+            types: vec![],
+            version: child_string(root, "version").map(|v| v.trim().to_string()),
+        };
+        for types in children_named(root, "types") {
+            let name = child_string(types, "name")
+                .map(|n| n.trim().to_string())
+                .ok_or_else(|| Error::XmlError("types element without a name".to_string()))?;
+            for member in children_named(types, "members") {
+                manifest.add(&name, member.text().unwrap_or_default().trim());
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        PackageManifest::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Serialize as an indented `package.xml`.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!("<Package xmlns=\"{}\">\n", METADATA_NAMESPACE));
+        for types in &self.types {
+            xml.push_str("    <types>\n");
+            for member in &types.members {
+                xml.push_str(&format!(
+                    "        <members>{}</members>\n",
+                    escape_xml(member)
+                ));
+            }
+            xml.push_str(&format!(
+                "        <name>{}</name>\n",
+                escape_xml(&types.name)
+            ));
+            xml.push_str("    </types>\n");
+        }
+        if let Some(version) = &self.version {
+            xml.push_str(&format!("    <version>{}</version>\n", escape_xml(version)));
+        }
+        xml.push_str("</Package>\n");
+        xml
+    }
+
+    /// One entry per component returned by `MetadataApi::list_metadata`.
+    pub fn from_file_properties(properties: &[FileProperties], version: &str) -> Self {
+        let mut manifest = PackageManifest::new(version);
+        for property in properties {
+            manifest.add(&property.type_name, &property.full_name);
+        }
+        manifest.sort();
+        manifest
+    }
+
+    /// Build a manifest from a source directory such as `src/` or `force-app/main/default/`.
+    ///
+    /// Both the Metadata API layout (`classes/Foo.cls`) and the source format layout
+    /// (`classes/Foo.cls-meta.xml`, `objects/Account/fields/Tier__c.field-meta.xml`) are
+    /// recognised. Directories of unknown types are skipped.
+    pub fn from_source_dir(root: impl AsRef<Path>, version: &str) -> Result<Self, Error> {
+        let root = root.as_ref();
+        let mut manifest = PackageManifest::new(version);
+
+        for (dir, type_name, suffix) in DIRECTORY_TYPES {
+            for entry in list_dir(&root.join(dir))? {
+                let path = entry.path();
+                if path.is_dir() {
+                    // Source format keeps each object and its children in a directory.
+                    if *type_name == "CustomObject" {
+                        add_object_dir(&mut manifest, &path)?;
+                    }
+                    continue;
+                }
+                if let Some(name) = member_name(&path, suffix) {
+                    manifest.add(type_name, &name);
+                }
+            }
+        }
+
+        for (dir, type_name) in BUNDLE_TYPES {
+            for entry in list_dir(&root.join(dir))? {
+                if entry.path().is_dir() {
+                    manifest.add(type_name, &file_name(&entry.path()));
+                }
+            }
+        }
+
+        for (dir, type_name) in FOLDER_TYPES {
+            for folder in list_dir(&root.join(dir))? {
+                let folder_path = folder.path();
+                if !folder_path.is_dir() {
+                    continue;
+                }
+                let folder_name = file_name(&folder_path);
+                manifest.add(type_name, &folder_name);
+                for entry in list_dir(&folder_path)? {
+                    let path = entry.path();
+                    if let Some(name) = member_name(&path, "") {
+                        manifest.add(type_name, &format!("{}/{}", folder_name, name));
+                    }
+                }
+            }
+        }
+
+        manifest.sort();
+        Ok(manifest)
+    }
+
+    /// Add a component, grouping it with other components of the same type.
+    pub fn add(&mut self, type_name: &str, member: &str) -> &mut Self {
+        match self.types.iter_mut().find(|t| t.name == type_name) {
+            Some(types) => {
+                if !types.members.iter().any(|m| m == member) {
+                    types.members.push(member.to_string());
+                }
+            }
+            None => self.types.push(PackageTypeMembers {
+                name: type_name.to_string(),
+                members: vec![member.to_string()],
+            }),
+        }
+        self
+    }
+
+    pub fn members(&self, type_name: &str) -> &[String] {
+        self.types
+            .iter()
+            .find(|t| t.name == type_name)
+            .map(|t| t.members.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether the component is listed by name or through a `*` wildcard.
+    pub fn contains(&self, type_name: &str, member: &str) -> bool {
+        self.members(type_name)
+            .iter()
+            .any(|m| m == member || m == "*")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.iter().all(|t| t.members.is_empty())
+    }
+
+    /// Sort types by name and members within each type, as Salesforce tools write them.
+    pub fn sort(&mut self) -> &mut Self {
+        self.types.sort_by(|a, b| a.name.cmp(&b.name));
+        for types in &mut self.types {
+            types.members.sort();
+        }
+        self
+    }
+
+    /// Add every component of `other`. The higher of the two API versions is kept.
+    pub fn merge(&mut self, other: &PackageManifest) -> &mut Self {
+        for types in &other.types {
+            for member in &types.members {
+                self.add(&types.name, member);
+            }
+        }
+        self.version = match (self.version.take(), &other.version) {
+            (Some(mine), Some(theirs)) => Some(if version_number(theirs) > version_number(&mine) {
+                theirs.clone()
+            } else {
+                mine
+            }),
+            (mine, theirs) => mine.or_else(|| theirs.clone()),
+        };
+        self.sort()
+    }
+
+    /// Components only in `newer` are added, components only in `self` are removed.
+    /// Members are compared by name, so `*` only matches another `*`.
+    pub fn diff(&self, newer: &PackageManifest) -> ManifestDiff {
+        let version = newer.version.as_deref().or(self.version.as_deref());
+        let mut diff = ManifestDiff {
+            added: PackageManifest {
+                types: vec![],
+                version: version.map(|v| v.to_string()),
+            },
+            removed: PackageManifest {
+                types: vec![],
+                version: version.map(|v| v.to_string()),
+            },
+        };
+        for types in &newer.types {
+            for member in &types.members {
+                if !self.members(&types.name).contains(member) {
+                    diff.added.add(&types.name, member);
+                }
+            }
+        }
+        for types in &self.types {
+            for member in &types.members {
+                if !newer.members(&types.name).contains(member) {
+                    diff.removed.add(&types.name, member);
+                }
+            }
+        }
+        diff.added.sort();
+        diff.removed.sort();
+        diff
+    }
+
+    /// Write the `<types>` and `<version>` elements into an enclosing element.
+    pub(crate) fn write_contents(&self, writer: &mut XmlWriter) {
+        for types in &self.types {
+            writer.start("types", &[]);
+            for member in &types.members {
+                writer.element("members", member);
+            }
+            writer.element("name", &types.name);
+            writer.end("types");
+        }
+        if let Some(version) = &self.version {
+            writer.element("version", version);
+        }
+    }
+}
+
+fn version_number(version: &str) -> f64 {
+    version.trim_start_matches('v').parse().unwrap_or(0.0)
+}
+
+/// Entries of a directory, or none when it does not exist. Hidden files are skipped.
+fn list_dir(dir: &Path) -> Result<Vec<fs::DirEntry>, Error> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut entries = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_name().to_string_lossy().starts_with('.') {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Component name of a file: `Foo.cls` and `Foo.cls-meta.xml` are both `Foo`.
+/// With a non-empty `suffix`, files with other extensions are ignored.
+fn member_name(path: &Path, suffix: &str) -> Option<String> {
+    if path.is_dir() {
+        return None;
+    }
+    let name = file_name(path);
+    let name = name.strip_suffix("-meta.xml").unwrap_or(&name);
+    let (stem, extension) = name.rsplit_once('.')?;
+    if !suffix.is_empty() && extension != suffix {
+        // Static resources keep their content under its own extension next to the meta file.
+        return if suffix == "resource" {
+            Some(stem.to_string())
+        } else {
+            None
+        };
+    }
+    Some(stem.to_string())
+}
+
+/// `objects/Account/` in source format: the object itself plus its fields, list views, etc.
+fn add_object_dir(manifest: &mut PackageManifest, dir: &Path) -> Result<(), Error> {
+    let object = file_name(dir);
+    manifest.add("CustomObject", &object);
+    for (child_dir, type_name) in OBJECT_CHILD_TYPES {
+        for entry in list_dir(&dir.join(child_dir))? {
+            if let Some(name) = member_name(&entry.path(), "") {
+                manifest.add(type_name, &format!("{}.{}", object, name));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rustforce-package-manifest-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn touch(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    #[test]
+    fn test_add_groups_by_type() {
+        let mut manifest = PackageManifest::new("60.0");
+        manifest
+            .add("ApexClass", "MyClass")
+            .add("CustomObject", "Invoice__c")
+            .add("ApexClass", "OtherClass")
+            .add("ApexClass", "MyClass");
+        assert_eq!(manifest.types.len(), 2);
+        assert_eq!(manifest.members("ApexClass"), ["MyClass", "OtherClass"]);
+        assert!(manifest.members("Flow").is_empty());
+    }
+
+    #[test]
+    fn test_write_contents() {
+        let mut manifest = PackageManifest::new("60.0");
+        manifest.add("CustomField", "Account.Tier__c");
+        let mut writer = XmlWriter::new();
+        writer.start("unpackaged", &[]);
+        manifest.write_contents(&mut writer);
+        writer.end("unpackaged");
+        assert_eq!(
+            writer.into_string(),
+            "<unpackaged><types><members>Account.Tier__c</members><name>CustomField</name>\
+             </types><version>60.0</version></unpackaged>"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Package xmlns="http://soap.sforce.com/2006/04/metadata">
+                <types>
+                    <members>*</members>
+                    <name>ApexClass</name>
+                </types>
+                <types>
+                    <members>Account.Tier__c</members>
+                    <members>Contact.Level__c</members>
+                    <name>CustomField</name>
+                </types>
+                <version>60.0</version>
+            </Package>"#;
+        let manifest = PackageManifest::parse(xml).unwrap();
+        assert_eq!(manifest.version.as_deref(), Some("60.0"));
+        assert_eq!(
+            manifest.members("CustomField"),
+            ["Account.Tier__c", "Contact.Level__c"]
+        );
+        assert!(manifest.contains("ApexClass", "AnyClass"));
+        assert!(!manifest.contains("CustomField", "Lead.Score__c"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            PackageManifest::parse("not xml"),
+            Err(Error::XmlError(_))
+        ));
+        assert!(matches!(
+            PackageManifest::parse("<Other/>"),
+            Err(Error::XmlError(_))
+        ));
+        assert!(matches!(
+            PackageManifest::parse("<Package><types><members>A</members></types></Package>"),
+            Err(Error::XmlError(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_xml_round_trip() {
+        let mut manifest = PackageManifest::new("60.0");
+        manifest
+            .add("ApexClass", "MyClass")
+            .add("Report", "Sales & Ops/Pipeline");
+        let xml = manifest.to_xml();
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <Package xmlns=\"http://soap.sforce.com/2006/04/metadata\">\n    \
+             <types>\n        <members>MyClass</members>\n        <name>ApexClass</name>\n    </types>\n    \
+             <types>\n        <members>Sales &amp; Ops/Pipeline</members>\n        <name>Report</name>\n    </types>\n    \
+             <version>60.0</version>\n\
+             </Package>\n"
+        );
+        assert_eq!(PackageManifest::parse(&xml).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = PackageManifest::new("59.0");
+        base.add("ApexClass", "B").add("Flow", "Onboarding");
+        let mut other = PackageManifest::new("60.0");
+        other.add("ApexClass", "A").add("ApexClass", "B");
+
+        base.merge(&other);
+        assert_eq!(base.version.as_deref(), Some("60.0"));
+        assert_eq!(base.types[0].name, "ApexClass");
+        assert_eq!(base.members("ApexClass"), ["A", "B"]);
+        assert_eq!(base.members("Flow"), ["Onboarding"]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut old = PackageManifest::new("59.0");
+        old.add("ApexClass", "Kept").add("ApexClass", "Dropped");
+        let mut new = PackageManifest::new("60.0");
+        new.add("ApexClass", "Kept")
+            .add("CustomObject", "Invoice__c");
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added.members("CustomObject"), ["Invoice__c"]);
+        assert!(diff.added.members("ApexClass").is_empty());
+        assert_eq!(diff.removed.members("ApexClass"), ["Dropped"]);
+        assert_eq!(diff.removed.version.as_deref(), Some("60.0"));
+        assert!(old.diff(&old).added.is_empty());
+    }
+
+    #[test]
+    fn test_from_file_properties() {
+        let properties = vec![
+            FileProperties {
+                full_name: "Zeta".to_string(),
+                type_name: "ApexClass".to_string(),
+                ..Default::default()
+            },
+            FileProperties {
+                full_name: "Alpha".to_string(),
+                type_name: "ApexClass".to_string(),
+                ..Default::default()
+            },
+        ];
+        let manifest = PackageManifest::from_file_properties(&properties, "60.0");
+        assert_eq!(manifest.members("ApexClass"), ["Alpha", "Zeta"]);
+    }
+
+    #[test]
+    fn test_from_source_dir_metadata_format() {
+        let dir = temp_dir("metadata-format");
+        touch(&dir, "classes/AccountService.cls");
+        touch(&dir, "classes/AccountService.cls-meta.xml");
+        touch(&dir, "classes/.DS_Store");
+        touch(&dir, "objects/Invoice__c.object");
+        touch(&dir, "staticresources/logo.png");
+        touch(&dir, "staticresources/logo.resource-meta.xml");
+        touch(&dir, "lwc/invoiceList/invoiceList.js");
+        touch(&dir, "reports/Sales/Pipeline.report");
+        touch(&dir, "unknown/Thing.txt");
+
+        let manifest = PackageManifest::from_source_dir(&dir, "60.0").unwrap();
+        assert_eq!(manifest.members("ApexClass"), ["AccountService"]);
+        assert_eq!(manifest.members("CustomObject"), ["Invoice__c"]);
+        assert_eq!(manifest.members("StaticResource"), ["logo"]);
+        assert_eq!(
+            manifest.members("LightningComponentBundle"),
+            ["invoiceList"]
+        );
+        assert_eq!(manifest.members("Report"), ["Sales", "Sales/Pipeline"]);
+        assert_eq!(manifest.types.len(), 5);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_from_source_dir_source_format() {
+        let dir = temp_dir("source-format");
+        touch(&dir, "objects/Account/Account.object-meta.xml");
+        touch(&dir, "objects/Account/fields/Tier__c.field-meta.xml");
+        touch(
+            &dir,
+            "objects/Account/listViews/AllAccounts.listView-meta.xml",
+        );
+        touch(&dir, "permissionsets/Billing.permissionset-meta.xml");
+
+        let manifest = PackageManifest::from_source_dir(&dir, "60.0").unwrap();
+        assert_eq!(manifest.members("CustomObject"), ["Account"]);
+        assert_eq!(manifest.members("CustomField"), ["Account.Tier__c"]);
+        assert_eq!(manifest.members("ListView"), ["Account.AllAccounts"]);
+        assert_eq!(manifest.members("PermissionSet"), ["Billing"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_from_missing_file() {
+        assert!(matches!(
+            PackageManifest::from_file("/nonexistent/package.xml"),
+            Err(Error::IoError(_))
+        ));
+    }
+}
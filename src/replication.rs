@@ -0,0 +1,190 @@
+use crate::client::rest_api::RestApi;
+use crate::errors::Error;
+use crate::responses::replication_response::DeletedRecord;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Salesforce only keeps replication data for the last 30 days.
+pub const REPLICATION_WINDOW_DAYS: i64 = 30;
+
+pub(crate) fn validate_window(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), Error> {
+    if end <= start {
+        return Err(Error::DateRangeError(format!(
+            "end {} must be after start {}",
+            end, start
+        )));
+    }
+    let earliest = now - Duration::days(REPLICATION_WINDOW_DAYS);
+    if start < earliest {
+        return Err(Error::DateRangeError(format!(
+            "start {} is older than {} days; replication data is only kept since {}",
+            start, REPLICATION_WINDOW_DAYS, earliest
+        )));
+    }
+    Ok(())
+}
+
+/// Changes returned by one `ReplicationCursor::poll`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplicationChanges {
+    pub updated_ids: Vec<String>,
+    pub deleted_records: Vec<DeletedRecord>,
+    pub latest_date_covered: DateTime<Utc>,
+}
+
+/// Incremental sync state for one object built on `get_updated` and `get_deleted`.
+///
+/// Each poll asks for changes since the last `latestDateCovered` and advances the
+/// cursor, so nothing is skipped between runs. The cursor is serializable and can be
+/// persisted between nightly jobs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplicationCursor {
+    sobject_name: String,
+    latest_date_covered: DateTime<Utc>,
+}
+
+impl ReplicationCursor {
+    pub fn new(sobject_name: &str, start: DateTime<Utc>) -> Self {
+        ReplicationCursor {
+            sobject_name: sobject_name.to_string(),
+            latest_date_covered: start,
+        }
+    }
+
+    pub fn sobject_name(&self) -> &str {
+        &self.sobject_name
+    }
+
+    pub fn latest_date_covered(&self) -> DateTime<Utc> {
+        self.latest_date_covered
+    }
+
+    /// Fetch everything updated or deleted since the last poll.
+    pub async fn poll(&mut self, api: &mut RestApi) -> Result<ReplicationChanges, Error> {
+        self.poll_until(api, Utc::now()).await
+    }
+
+    pub async fn poll_until(
+        &mut self,
+        api: &mut RestApi,
+        end: DateTime<Utc>,
+    ) -> Result<ReplicationChanges, Error> {
+        let start = self.latest_date_covered;
+        let updated = api.get_updated(&self.sobject_name, start, end).await?;
+        let deleted = api.get_deleted(&self.sobject_name, start, end).await?;
+
+        // Only advance as far as both feeds are known to be complete
+        let latest_date_covered = updated.latest_date_covered.min(deleted.latest_date_covered);
+        self.latest_date_covered = latest_date_covered;
+
+        Ok(ReplicationChanges {
+            updated_ids: updated.ids,
+            deleted_records: deleted.deleted_records,
+            latest_date_covered,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::client::Client;
+    use crate::datetime::format_datetime;
+    use chrono::{DurationRound, TimeZone};
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_window() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 3, 30, 0, 0, 0).unwrap();
+        assert!(validate_window(start, now, now).is_ok());
+
+        match validate_window(now, start, now).unwrap_err() {
+            Error::DateRangeError(msg) => assert!(msg.contains("must be after start")),
+            e => panic!("Expected DateRangeError, got {:?}", e),
+        }
+
+        let too_old = Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap();
+        match validate_window(too_old, now, now).unwrap_err() {
+            Error::DateRangeError(msg) => assert!(msg.contains("older than 30 days")),
+            e => panic!("Expected DateRangeError, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_advances_cursor() {
+        let mut server = Server::new_async().await;
+        let now = Utc::now().duration_trunc(Duration::minutes(1)).unwrap();
+        let start = now - Duration::days(1);
+        let covered_updated = now - Duration::minutes(1);
+        let covered_deleted = now - Duration::minutes(2);
+        let window = Matcher::AllOf(vec![
+            Matcher::UrlEncoded("start".into(), format_datetime(&start)),
+            Matcher::UrlEncoded("end".into(), format_datetime(&now)),
+        ]);
+
+        let updated = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/updated/")
+            .match_query(window.clone())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "ids": ["001xx1"],
+                    "latestDateCovered": format_datetime(&covered_updated)
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let deleted = server
+            .mock("GET", "/services/data/v60.0/sobjects/Account/deleted/")
+            .match_query(window)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "deletedRecords": [{"id": "001xx2", "deletedDate": format_datetime(&start)}],
+                    "earliestDateAvailable": format_datetime(&start),
+                    "latestDateCovered": format_datetime(&covered_deleted)
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = Client::new();
+        client.set_instance_url(&server.url());
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        let mut api = RestApi::new(client);
+
+        let mut cursor = ReplicationCursor::new("Account", start);
+        let changes = cursor.poll_until(&mut api, now).await.unwrap();
+        assert_eq!(changes.updated_ids, vec!["001xx1".to_string()]);
+        assert_eq!(changes.deleted_records[0].id, "001xx2");
+        assert_eq!(changes.latest_date_covered, covered_deleted);
+        assert_eq!(cursor.latest_date_covered(), covered_deleted);
+
+        updated.assert_async().await;
+        deleted.assert_async().await;
+    }
+
+    #[test]
+    fn test_cursor_serializes() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 30, 0, 0, 0).unwrap();
+        let cursor = ReplicationCursor::new("Account", start);
+        let json = serde_json::to_string(&cursor).unwrap();
+        let back: ReplicationCursor = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cursor);
+        assert_eq!(back.sobject_name(), "Account");
+    }
+}
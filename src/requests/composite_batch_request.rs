@@ -0,0 +1,132 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// HTTP methods accepted by a composite batch subrequest.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BatchMethod {
+    Get,
+    Post,
+    Patch,
+    Delete,
+}
+
+/// A single subrequest executed as part of `RestApi::composite_batch`.
+///
+/// `url` is relative to the versioned data path, e.g. `sobjects/Account/describe`
+/// or `limits`. The API version prefix is added from `Client::version` when the
+/// batch is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSubrequest {
+    pub method: BatchMethod,
+    pub url: String,
+    pub rich_input: Option<Value>,
+}
+
+impl BatchSubrequest {
+    pub fn new(method: BatchMethod, url: &str) -> Self {
+        BatchSubrequest {
+            method,
+            url: url.to_string(),
+            rich_input: None,
+        }
+    }
+
+    pub fn get(url: &str) -> Self {
+        BatchSubrequest::new(BatchMethod::Get, url)
+    }
+
+    pub fn post(url: &str, body: Value) -> Self {
+        BatchSubrequest::new(BatchMethod::Post, url).with_body(body)
+    }
+
+    pub fn patch(url: &str, body: Value) -> Self {
+        BatchSubrequest::new(BatchMethod::Patch, url).with_body(body)
+    }
+
+    pub fn delete(url: &str) -> Self {
+        BatchSubrequest::new(BatchMethod::Delete, url)
+    }
+
+    pub fn with_body(mut self, body: Value) -> Self {
+        self.rich_input = Some(body);
+        self
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CompositeBatchRequest {
+    pub batch_requests: Vec<VersionedSubrequest>,
+    pub halt_on_error: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VersionedSubrequest {
+    pub method: BatchMethod,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rich_input: Option<Value>,
+}
+
+impl CompositeBatchRequest {
+    pub(crate) fn new(version: &str, requests: Vec<BatchSubrequest>, halt_on_error: bool) -> Self {
+        let batch_requests = requests
+            .into_iter()
+            .map(|r| VersionedSubrequest {
+                method: r.method,
+                url: format!("{}/{}", version, r.url.trim_start_matches('/')),
+                rich_input: r.rich_input,
+            })
+            .collect();
+        CompositeBatchRequest {
+            batch_requests,
+            halt_on_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_constructors() {
+        let get = BatchSubrequest::get("limits");
+        assert_eq!(get.method, BatchMethod::Get);
+        assert_eq!(get.url, "limits");
+        assert_eq!(get.rich_input, None);
+
+        let patch = BatchSubrequest::patch("sobjects/Account/001xx", json!({"Name": "Acme"}));
+        assert_eq!(patch.method, BatchMethod::Patch);
+        assert_eq!(patch.rich_input, Some(json!({"Name": "Acme"})));
+
+        let delete = BatchSubrequest::delete("sobjects/Account/001xx");
+        assert_eq!(delete.method, BatchMethod::Delete);
+    }
+
+    #[test]
+    fn test_serialize_prefixes_version() {
+        let request = CompositeBatchRequest::new(
+            "v60.0",
+            vec![
+                BatchSubrequest::get("/limits"),
+                BatchSubrequest::post("sobjects/Account", json!({"Name": "Acme"})),
+            ],
+            true,
+        );
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "haltOnError": true,
+                "batchRequests": [
+                    {"method": "GET", "url": "v60.0/limits"},
+                    {"method": "POST", "url": "v60.0/sobjects/Account", "richInput": {"Name": "Acme"}}
+                ]
+            })
+        );
+    }
+}
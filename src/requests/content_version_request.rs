@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+/// JSON part sent alongside the file data when uploading a `ContentVersion`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/dome_sobject_insert_update_blob.htm>
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContentVersionMetadata {
+    pub title: String,
+    /// File name including extension; also used as the file name of the binary part.
+    pub path_on_client: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Record the new file is shared with on creation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_publish_location_id: Option<String>,
+    /// Set to upload a new version of an existing file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_document_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_for_change: Option<String>,
+}
+
+impl ContentVersionMetadata {
+    pub fn new(title: &str, path_on_client: &str) -> Self {
+        ContentVersionMetadata {
+            title: title.to_string(),
+            path_on_client: path_on_client.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Permission granted to a record linked to a file through `ContentDocumentLink`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareType {
+    #[serde(rename = "V")]
+    Viewer,
+    #[serde(rename = "C")]
+    Collaborator,
+    #[serde(rename = "I")]
+    Inferred,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct ContentDocumentLinkRequest<'a> {
+    pub content_document_id: &'a str,
+    pub linked_entity_id: &'a str,
+    pub share_type: ShareType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_metadata() {
+        let mut metadata = ContentVersionMetadata::new("Q3 Report", "q3.pdf");
+        metadata.first_publish_location_id = Some("001xx".to_string());
+        assert_eq!(
+            serde_json::to_value(&metadata).unwrap(),
+            json!({
+                "Title": "Q3 Report",
+                "PathOnClient": "q3.pdf",
+                "FirstPublishLocationId": "001xx"
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_link() {
+        let link = ContentDocumentLinkRequest {
+            content_document_id: "069xx",
+            linked_entity_id: "001xx",
+            share_type: ShareType::Viewer,
+        };
+        assert_eq!(
+            serde_json::to_value(&link).unwrap(),
+            json!({"ContentDocumentId": "069xx", "LinkedEntityId": "001xx", "ShareType": "V"})
+        );
+    }
+}
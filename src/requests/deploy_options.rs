@@ -0,0 +1,117 @@
+use crate::xml::XmlWriter;
+
+/// Which Apex tests run as part of a deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployTestLevel {
+    NoTestRun,
+    RunSpecifiedTests,
+    RunLocalTests,
+    RunAllTestsInOrg,
+}
+
+impl DeployTestLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeployTestLevel::NoTestRun => "NoTestRun",
+            DeployTestLevel::RunSpecifiedTests => "RunSpecifiedTests",
+            DeployTestLevel::RunLocalTests => "RunLocalTests",
+            DeployTestLevel::RunAllTestsInOrg => "RunAllTestsInOrg",
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_deploy.htm#deploy_options>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployOptions {
+    /// Validate the deployment without saving any changes.
+    pub check_only: bool,
+    pub rollback_on_error: bool,
+    /// The zip contains one package rather than a directory of packages.
+    pub single_package: bool,
+    pub ignore_warnings: bool,
+    pub purge_on_delete: bool,
+    pub test_level: Option<DeployTestLevel>,
+    /// Test classes to run with `DeployTestLevel::RunSpecifiedTests`.
+    pub run_tests: Vec<String>,
+}
+
+impl Default for DeployOptions {
+    fn default() -> Self {
+        DeployOptions {
+            check_only: false,
+            rollback_on_error: true,
+            single_package: true,
+            ignore_warnings: false,
+            purge_on_delete: false,
+            test_level: None,
+            run_tests: vec![],
+        }
+    }
+}
+
+impl DeployOptions {
+    pub fn new() -> Self {
+        DeployOptions::default()
+    }
+
+    pub fn with_check_only(mut self, check_only: bool) -> Self {
+        self.check_only = check_only;
+        self
+    }
+
+    pub fn with_test_level(mut self, test_level: DeployTestLevel) -> Self {
+        self.test_level = Some(test_level);
+        self
+    }
+
+    /// Run only these test classes; also sets the test level to `RunSpecifiedTests`.
+    pub fn with_run_tests(mut self, class_names: &[&str]) -> Self {
+        self.test_level = Some(DeployTestLevel::RunSpecifiedTests);
+        self.run_tests = class_names.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        writer.start("DeployOptions", &[]);
+        writer.element("checkOnly", &self.check_only.to_string());
+        writer.element("ignoreWarnings", &self.ignore_warnings.to_string());
+        writer.element("purgeOnDelete", &self.purge_on_delete.to_string());
+        writer.element("rollbackOnError", &self.rollback_on_error.to_string());
+        for test in &self.run_tests {
+            writer.element("runTests", test);
+        }
+        writer.element("singlePackage", &self.single_package.to_string());
+        if let Some(test_level) = self.test_level {
+            writer.element("testLevel", test_level.as_str());
+        }
+        writer.end("DeployOptions");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let options = DeployOptions::new();
+        assert!(!options.check_only);
+        assert!(options.rollback_on_error);
+        assert!(options.single_package);
+        assert!(options.test_level.is_none());
+    }
+
+    #[test]
+    fn test_write_xml() {
+        let options = DeployOptions::new()
+            .with_check_only(true)
+            .with_run_tests(&["AccountTest", "LeadTest"]);
+        let mut writer = XmlWriter::new();
+        options.write_xml(&mut writer);
+        let xml = writer.into_string();
+        assert!(xml.starts_with("<DeployOptions><checkOnly>true</checkOnly>"));
+        assert!(xml.contains("<runTests>AccountTest</runTests><runTests>LeadTest</runTests>"));
+        assert!(xml.contains("<testLevel>RunSpecifiedTests</testLevel>"));
+        assert!(xml.ends_with("</DeployOptions>"));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::xml::XmlWriter;
+
+/// Selects the components of one metadata type for `listMetadata`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_listmetadata.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListMetadataQuery {
+    /// Metadata type, e.g. `CustomObject` or `Report`.
+    pub type_name: String,
+    /// Required for folder-based types such as `Report`, `Dashboard`, `Document` and `EmailTemplate`.
+    pub folder: Option<String>,
+}
+
+impl ListMetadataQuery {
+    pub fn new(type_name: &str) -> Self {
+        ListMetadataQuery {
+            type_name: type_name.to_string(),
+            folder: None,
+        }
+    }
+
+    pub fn in_folder(type_name: &str, folder: &str) -> Self {
+        ListMetadataQuery {
+            type_name: type_name.to_string(),
+            folder: Some(folder.to_string()),
+        }
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        writer.start("queries", &[]);
+        if let Some(folder) = &self.folder {
+            writer.element("folder", folder);
+        }
+        writer.element("type", &self.type_name);
+        writer.end("queries");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_xml() {
+        let mut writer = XmlWriter::new();
+        ListMetadataQuery::new("CustomObject").write_xml(&mut writer);
+        ListMetadataQuery::in_folder("Report", "Sales & Ops").write_xml(&mut writer);
+        assert_eq!(
+            writer.into_string(),
+            "<queries><type>CustomObject</type></queries>\
+             <queries><folder>Sales &amp; Ops</folder><type>Report</type></queries>"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod composite_batch_request;
+pub mod content_version_request;
+pub mod deploy_options;
+pub mod list_metadata_query;
+pub mod partner_request;
+pub mod request_options;
+pub mod retrieve_request;
+pub mod run_tests_request;
+pub mod trace_flag_request;
@@ -0,0 +1,393 @@
+use crate::errors::Error;
+use crate::xml::XmlWriter;
+use serde_json::{Map, Value};
+
+const SOBJECT_NAMESPACE: &str = "urn:sobject.partner.soap.sforce.com";
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Merges up to two records into a master record of the same type.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_merge.htm>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeRequest {
+    /// `Account`, `Contact`, `Lead` or `Case`.
+    pub sobject_type: String,
+    pub master_record_id: String,
+    /// Field values to set on the master record; `null` clears a field.
+    pub field_overrides: Map<String, Value>,
+    pub record_to_merge_ids: Vec<String>,
+}
+
+impl MergeRequest {
+    pub fn new(sobject_type: &str, master_record_id: &str, record_to_merge_ids: &[&str]) -> Self {
+        MergeRequest {
+            sobject_type: sobject_type.to_string(),
+            master_record_id: master_record_id.to_string(),
+            field_overrides: Map::new(),
+            record_to_merge_ids: record_to_merge_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+        }
+    }
+
+    /// Set a field on the master record, e.g. to keep a duplicate's value.
+    pub fn with_field_override(mut self, field: &str, value: Value) -> Self {
+        self.field_overrides.insert(field.to_string(), value);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.record_to_merge_ids.is_empty() || self.record_to_merge_ids.len() > 2 {
+            return Err(Error::ConfigError(format!(
+                "merge takes one or two duplicate records, got {}",
+                self.record_to_merge_ids.len()
+            )));
+        }
+        if self.record_to_merge_ids.contains(&self.master_record_id) {
+            return Err(Error::ConfigError(
+                "the master record cannot be merged into itself".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        writer.start("request", &[]);
+        writer.start("masterRecord", &[("xmlns:sf", SOBJECT_NAMESPACE)]);
+        writer.element("sf:type", &self.sobject_type);
+        for (field, value) in &self.field_overrides {
+            if value.is_null() {
+                writer.element("sf:fieldsToNull", field);
+            }
+        }
+        writer.element("sf:Id", &self.master_record_id);
+        for (field, value) in &self.field_overrides {
+            writer.value(&format!("sf:{}", field), value);
+        }
+        writer.end("masterRecord");
+        for id in &self.record_to_merge_ids {
+            writer.element("recordToMergeIds", id);
+        }
+        writer.end("request");
+    }
+}
+
+/// Converts a lead into an account, a contact and optionally an opportunity.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_convertlead.htm>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeadConvert {
+    pub lead_id: String,
+    /// A `LeadStatus` marked as converted, e.g. `Closed - Converted`.
+    pub converted_status: String,
+    /// Merge into an existing account instead of creating one.
+    pub account_id: Option<String>,
+    /// Merge into an existing contact; requires `account_id`.
+    pub contact_id: Option<String>,
+    pub do_not_create_opportunity: bool,
+    /// Defaults to the lead's company name.
+    pub opportunity_name: Option<String>,
+    pub overwrite_lead_source: bool,
+    pub owner_id: Option<String>,
+    pub send_notification_email: bool,
+}
+
+impl LeadConvert {
+    pub fn new(lead_id: &str, converted_status: &str) -> Self {
+        LeadConvert {
+            lead_id: lead_id.to_string(),
+            converted_status: converted_status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Attach the contact to an existing account instead of creating one.
+    pub fn with_account_id(mut self, account_id: &str) -> Self {
+        self.account_id = Some(account_id.to_string());
+        self
+    }
+
+    /// Merge the lead into an existing contact of the account given with `with_account_id`.
+    pub fn with_contact_id(mut self, contact_id: &str) -> Self {
+        self.contact_id = Some(contact_id.to_string());
+        self
+    }
+
+    pub fn with_opportunity_name(mut self, name: &str) -> Self {
+        self.opportunity_name = Some(name.to_string());
+        self.do_not_create_opportunity = false;
+        self
+    }
+
+    pub fn without_opportunity(mut self) -> Self {
+        self.do_not_create_opportunity = true;
+        self.opportunity_name = None;
+        self
+    }
+
+    pub fn with_owner_id(mut self, owner_id: &str) -> Self {
+        self.owner_id = Some(owner_id.to_string());
+        self
+    }
+
+    pub fn with_send_notification_email(mut self, send: bool) -> Self {
+        self.send_notification_email = send;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.contact_id.is_some() && self.account_id.is_none() {
+            return Err(Error::ConfigError(
+                "converting into an existing contact requires its account_id".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        writer.start("leadConverts", &[]);
+        if let Some(account_id) = &self.account_id {
+            writer.element("accountId", account_id);
+        }
+        if let Some(contact_id) = &self.contact_id {
+            writer.element("contactId", contact_id);
+        }
+        writer.element("convertedStatus", &self.converted_status);
+        writer.element(
+            "doNotCreateOpportunity",
+            &self.do_not_create_opportunity.to_string(),
+        );
+        writer.element("leadId", &self.lead_id);
+        if let Some(opportunity_name) = &self.opportunity_name {
+            writer.element("opportunityName", opportunity_name);
+        }
+        writer.element(
+            "overwriteLeadSource",
+            &self.overwrite_lead_source.to_string(),
+        );
+        if let Some(owner_id) = &self.owner_id {
+            writer.element("ownerId", owner_id);
+        }
+        writer.element(
+            "sendNotificationEmail",
+            &self.send_notification_email.to_string(),
+        );
+        writer.end("leadConverts");
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAction {
+    Approve,
+    Reject,
+    /// Recall the request; only the submitter or an administrator can do this.
+    Removed,
+}
+
+impl ProcessAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProcessAction::Approve => "Approve",
+            ProcessAction::Reject => "Reject",
+            ProcessAction::Removed => "Removed",
+        }
+    }
+}
+
+/// Submits a record for approval or acts on a pending approval.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_process.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessRequest {
+    Submit {
+        object_id: String,
+        comments: Option<String>,
+        next_approver_ids: Vec<String>,
+        /// Runs the default process when not set.
+        process_definition_name_or_id: Option<String>,
+        skip_entry_criteria: bool,
+    },
+    Workitem {
+        workitem_id: String,
+        action: ProcessAction,
+        comments: Option<String>,
+        next_approver_ids: Vec<String>,
+    },
+}
+
+impl ProcessRequest {
+    pub fn submit(object_id: &str) -> Self {
+        ProcessRequest::Submit {
+            object_id: object_id.to_string(),
+            comments: None,
+            next_approver_ids: vec![],
+            process_definition_name_or_id: None,
+            skip_entry_criteria: false,
+        }
+    }
+
+    pub fn workitem(workitem_id: &str, action: ProcessAction) -> Self {
+        ProcessRequest::Workitem {
+            workitem_id: workitem_id.to_string(),
+            action,
+            comments: None,
+            next_approver_ids: vec![],
+        }
+    }
+
+    pub fn with_comments(mut self, text: &str) -> Self {
+        match &mut self {
+            ProcessRequest::Submit { comments, .. } | ProcessRequest::Workitem { comments, .. } => {
+                *comments = Some(text.to_string())
+            }
+        }
+        self
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        match self {
+            ProcessRequest::Submit {
+                object_id,
+                comments,
+                next_approver_ids,
+                process_definition_name_or_id,
+                skip_entry_criteria,
+            } => {
+                writer.start(
+                    "actions",
+                    &[
+                        ("xmlns:xsi", XSI_NAMESPACE),
+                        ("xsi:type", "ProcessSubmitRequest"),
+                    ],
+                );
+                write_common(writer, comments, next_approver_ids);
+                writer.element("objectId", object_id);
+                if let Some(process) = process_definition_name_or_id {
+                    writer.element("processDefinitionNameOrId", process);
+                }
+                writer.element("skipEntryCriteria", &skip_entry_criteria.to_string());
+            }
+            ProcessRequest::Workitem {
+                workitem_id,
+                action,
+                comments,
+                next_approver_ids,
+            } => {
+                writer.start(
+                    "actions",
+                    &[
+                        ("xmlns:xsi", XSI_NAMESPACE),
+                        ("xsi:type", "ProcessWorkitemRequest"),
+                    ],
+                );
+                write_common(writer, comments, next_approver_ids);
+                writer.element("action", action.as_str());
+                writer.element("workitemId", workitem_id);
+            }
+        }
+        writer.end("actions");
+    }
+}
+
+fn write_common(writer: &mut XmlWriter, comments: &Option<String>, next_approver_ids: &[String]) {
+    if let Some(comments) = comments {
+        writer.element("comments", comments);
+    }
+    for id in next_approver_ids {
+        writer.element("nextApproverIds", id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_request_xml() {
+        let mut request = MergeRequest::new("Account", "001A", &["001B"]);
+        request
+            .field_overrides
+            .insert("Name".to_string(), json!("Acme & Co"));
+        request
+            .field_overrides
+            .insert("Website".to_string(), Value::Null);
+        let mut writer = XmlWriter::new();
+        request.write_xml(&mut writer);
+        assert_eq!(
+            writer.into_string(),
+            "<request><masterRecord xmlns:sf=\"urn:sobject.partner.soap.sforce.com\">\
+             <sf:type>Account</sf:type><sf:fieldsToNull>Website</sf:fieldsToNull>\
+             <sf:Id>001A</sf:Id><sf:Name>Acme &amp; Co</sf:Name></masterRecord>\
+             <recordToMergeIds>001B</recordToMergeIds></request>"
+        );
+    }
+
+    #[test]
+    fn test_lead_convert_xml() {
+        let convert = LeadConvert {
+            account_id: Some("001A".to_string()),
+            do_not_create_opportunity: true,
+            ..LeadConvert::new("00QA", "Closed - Converted")
+        };
+        let mut writer = XmlWriter::new();
+        convert.write_xml(&mut writer);
+        assert_eq!(
+            writer.into_string(),
+            "<leadConverts><accountId>001A</accountId>\
+             <convertedStatus>Closed - Converted</convertedStatus>\
+             <doNotCreateOpportunity>true</doNotCreateOpportunity><leadId>00QA</leadId>\
+             <overwriteLeadSource>false</overwriteLeadSource>\
+             <sendNotificationEmail>false</sendNotificationEmail></leadConverts>"
+        );
+    }
+
+    #[test]
+    fn test_merge_request_validate() {
+        assert!(MergeRequest::new("Account", "001A", &["001B", "001C"])
+            .validate()
+            .is_ok());
+        assert!(MergeRequest::new("Account", "001A", &[])
+            .validate()
+            .is_err());
+        assert!(
+            MergeRequest::new("Account", "001A", &["001B", "001C", "001D"])
+                .validate()
+                .is_err()
+        );
+        assert!(MergeRequest::new("Account", "001A", &["001A"])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_lead_convert_builders() {
+        let convert = LeadConvert::new("00QA", "Closed - Converted")
+            .with_opportunity_name("Renewal")
+            .without_opportunity();
+        assert!(convert.do_not_create_opportunity);
+        assert_eq!(convert.opportunity_name, None);
+
+        let convert = LeadConvert::new("00QA", "Closed - Converted").with_contact_id("003A");
+        assert!(matches!(convert.validate(), Err(Error::ConfigError(_))));
+        assert!(convert.with_account_id("001A").validate().is_ok());
+    }
+
+    #[test]
+    fn test_process_request_xml() {
+        let mut writer = XmlWriter::new();
+        ProcessRequest::submit("006A")
+            .with_comments("Please approve")
+            .write_xml(&mut writer);
+        let xml = writer.into_string();
+        assert!(xml.contains("xsi:type=\"ProcessSubmitRequest\""));
+        assert!(xml.contains("<comments>Please approve</comments><objectId>006A</objectId>"));
+
+        let mut writer = XmlWriter::new();
+        ProcessRequest::workitem("04iA", ProcessAction::Reject).write_xml(&mut writer);
+        let xml = writer.into_string();
+        assert!(xml.contains("xsi:type=\"ProcessWorkitemRequest\""));
+        assert!(xml.contains("<action>Reject</action><workitemId>04iA</workitemId>"));
+    }
+}
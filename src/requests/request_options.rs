@@ -0,0 +1,263 @@
+use crate::errors::Error;
+
+/// Smallest and largest `batchSize` accepted in `Sforce-Query-Options`.
+const QUERY_BATCH_SIZE_RANGE: std::ops::RangeInclusive<u32> = 200..=2000;
+
+/// Which assignment rule runs when Cases or Leads are created or updated.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_autoassign.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoAssign {
+    /// Run the active assignment rule (Salesforce's default).
+    Active,
+    /// Don't run any assignment rule.
+    None,
+    /// Run a specific assignment rule by Id.
+    Rule(String),
+}
+
+impl AutoAssign {
+    fn header_value(&self) -> String {
+        match self {
+            AutoAssign::Active => "TRUE".to_string(),
+            AutoAssign::None => "FALSE".to_string(),
+            AutoAssign::Rule(id) => id.clone(),
+        }
+    }
+}
+
+/// `Sforce-Duplicate-Rule-Header` settings for saves that hit a duplicate rule.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_duplicaterules.htm>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DuplicateRuleHeader {
+    /// Save the record even if it is flagged as a duplicate with an alert.
+    pub allow_save: bool,
+    pub include_record_details: bool,
+    /// Apply sharing rules of the current user when looking for duplicates.
+    pub run_as_current_user: bool,
+}
+
+impl DuplicateRuleHeader {
+    fn header_value(&self) -> String {
+        format!(
+            "allowSave={}, includeRecordDetails={}, runAsCurrentUser={}",
+            self.allow_save, self.include_record_details, self.run_as_current_user
+        )
+    }
+}
+
+/// Whether a request updates the user's Most Recently Used list.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_mru.htm>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MruUpdate {
+    Update,
+    Skip,
+}
+
+/// Optional Salesforce request headers, sent with every call of the `Client` they're set on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RequestOptions {
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_queryoptions.htm>
+    pub query_batch_size: Option<u32>,
+    pub auto_assign: Option<AutoAssign>,
+    /// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/headers_calloptions.htm>
+    pub call_options_client: Option<String>,
+    pub call_options_default_namespace: Option<String>,
+    pub duplicate_rule: Option<DuplicateRuleHeader>,
+    pub mru: Option<MruUpdate>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        RequestOptions::default()
+    }
+
+    /// Records returned per page by `query`; Salesforce accepts 200 - 2000.
+    pub fn with_query_batch_size(mut self, batch_size: u32) -> Result<Self, Error> {
+        self.query_batch_size = Some(batch_size);
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn with_auto_assign(mut self, auto_assign: AutoAssign) -> Self {
+        self.auto_assign = Some(auto_assign);
+        self
+    }
+
+    /// Client identifier used to attribute API calls, e.g. for per-app limits.
+    pub fn with_call_options_client(mut self, client: &str) -> Self {
+        self.call_options_client = Some(client.to_string());
+        self
+    }
+
+    pub fn with_call_options_default_namespace(mut self, namespace: &str) -> Self {
+        self.call_options_default_namespace = Some(namespace.to_string());
+        self
+    }
+
+    pub fn with_duplicate_rule(mut self, duplicate_rule: DuplicateRuleHeader) -> Self {
+        self.duplicate_rule = Some(duplicate_rule);
+        self
+    }
+
+    pub fn with_mru(mut self, mru: MruUpdate) -> Self {
+        self.mru = Some(mru);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == RequestOptions::default()
+    }
+
+    /// Checks values the public fields may have been set to directly.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self.query_batch_size {
+            Some(batch_size) if !QUERY_BATCH_SIZE_RANGE.contains(&batch_size) => {
+                Err(Error::ConfigError(format!(
+                    "Query batch size must be between {} and {}, got {}",
+                    QUERY_BATCH_SIZE_RANGE.start(),
+                    QUERY_BATCH_SIZE_RANGE.end(),
+                    batch_size
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Options are validated when set on a `Client`, so building the headers cannot fail.
+    pub(crate) fn to_headers(&self) -> Vec<(String, String)> {
+        let mut headers = vec![];
+
+        if let Some(batch_size) = self.query_batch_size {
+            headers.push((
+                "Sforce-Query-Options".to_string(),
+                format!("batchSize={}", batch_size),
+            ));
+        }
+
+        if let Some(auto_assign) = &self.auto_assign {
+            headers.push(("Sforce-Auto-Assign".to_string(), auto_assign.header_value()));
+        }
+
+        let call_options: Vec<String> = [
+            ("client", &self.call_options_client),
+            ("defaultNamespace", &self.call_options_default_namespace),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}={}", name, v)))
+        .collect();
+        if !call_options.is_empty() {
+            headers.push(("Sforce-Call-Options".to_string(), call_options.join(", ")));
+        }
+
+        if let Some(duplicate_rule) = &self.duplicate_rule {
+            headers.push((
+                "Sforce-Duplicate-Rule-Header".to_string(),
+                duplicate_rule.header_value(),
+            ));
+        }
+
+        if let Some(mru) = self.mru {
+            let update = mru == MruUpdate::Update;
+            headers.push(("Sforce-Mru".to_string(), format!("updateMru={}", update)));
+        }
+
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_empty_options_have_no_headers() {
+        let options = RequestOptions::new();
+        assert!(options.is_empty());
+        assert!(options.to_headers().is_empty());
+    }
+
+    #[test]
+    fn test_all_headers() {
+        let options = RequestOptions::new()
+            .with_query_batch_size(500)
+            .unwrap()
+            .with_auto_assign(AutoAssign::Rule("01Qxx0000000001".to_string()))
+            .with_call_options_client("my-app")
+            .with_call_options_default_namespace("ns")
+            .with_duplicate_rule(DuplicateRuleHeader {
+                allow_save: true,
+                ..Default::default()
+            })
+            .with_mru(MruUpdate::Update);
+        let headers = options.to_headers();
+
+        assert_eq!(headers.len(), 5);
+        assert_eq!(
+            header(&headers, "Sforce-Query-Options"),
+            Some("batchSize=500")
+        );
+        assert_eq!(
+            header(&headers, "Sforce-Auto-Assign"),
+            Some("01Qxx0000000001")
+        );
+        assert_eq!(
+            header(&headers, "Sforce-Call-Options"),
+            Some("client=my-app, defaultNamespace=ns")
+        );
+        assert_eq!(
+            header(&headers, "Sforce-Duplicate-Rule-Header"),
+            Some("allowSave=true, includeRecordDetails=false, runAsCurrentUser=false")
+        );
+        assert_eq!(header(&headers, "Sforce-Mru"), Some("updateMru=true"));
+    }
+
+    #[test]
+    fn test_auto_assign_values() {
+        assert_eq!(AutoAssign::Active.header_value(), "TRUE");
+        assert_eq!(AutoAssign::None.header_value(), "FALSE");
+    }
+
+    #[test]
+    fn test_call_options_client_only() {
+        let headers = RequestOptions::new()
+            .with_call_options_client("my-app")
+            .to_headers();
+        assert_eq!(
+            header(&headers, "Sforce-Call-Options"),
+            Some("client=my-app")
+        );
+    }
+
+    #[test]
+    fn test_mru_skip() {
+        let headers = RequestOptions::new().with_mru(MruUpdate::Skip).to_headers();
+        assert_eq!(header(&headers, "Sforce-Mru"), Some("updateMru=false"));
+    }
+
+    #[test]
+    fn test_batch_size_out_of_range() {
+        for size in [0, 199, 2001] {
+            match RequestOptions::new().with_query_batch_size(size) {
+                Err(Error::ConfigError(msg)) => assert!(msg.contains(&size.to_string())),
+                other => panic!("Expected ConfigError, got {:?}", other),
+            }
+        }
+        assert!(RequestOptions::new().with_query_batch_size(2000).is_ok());
+
+        let options = RequestOptions {
+            query_batch_size: Some(5000),
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::package_manifest::PackageManifest;
+use crate::xml::XmlWriter;
+
+/// Retrieves the components listed in a package manifest.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_retrieverequest.htm>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetrieveRequest {
+    /// Metadata API version of the retrieved files, e.g. `60.0`.
+    pub api_version: String,
+    pub unpackaged: PackageManifest,
+    /// Put the files at the root of the zip instead of under `unpackaged/`.
+    pub single_package: bool,
+}
+
+impl RetrieveRequest {
+    pub fn new(api_version: &str, unpackaged: PackageManifest) -> Self {
+        RetrieveRequest {
+            api_version: api_version.to_string(),
+            unpackaged,
+            single_package: true,
+        }
+    }
+
+    pub fn with_single_package(mut self, single_package: bool) -> Self {
+        self.single_package = single_package;
+        self
+    }
+
+    pub(crate) fn write_xml(&self, writer: &mut XmlWriter) {
+        writer.start("retrieveRequest", &[]);
+        writer.element("apiVersion", &self.api_version);
+        writer.element("singlePackage", &self.single_package.to_string());
+        writer.start("unpackaged", &[]);
+        self.unpackaged.write_contents(writer);
+        writer.end("unpackaged");
+        writer.end("retrieveRequest");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_xml() {
+        let mut manifest = PackageManifest::new("60.0");
+        manifest.add("ApexClass", "MyClass");
+        let request = RetrieveRequest::new("60.0", manifest);
+        let mut writer = XmlWriter::new();
+        request.write_xml(&mut writer);
+        assert_eq!(
+            writer.into_string(),
+            "<retrieveRequest><apiVersion>60.0</apiVersion><singlePackage>true</singlePackage>\
+             <unpackaged><types><members>MyClass</members><name>ApexClass</name></types>\
+             <version>60.0</version></unpackaged></retrieveRequest>"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+/// Which tests Salesforce runs when no classes are listed explicitly.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestLevel {
+    RunSpecifiedTests,
+    RunLocalTests,
+    RunAllTestsInOrg,
+}
+
+/// A test class, optionally limited to some of its methods.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestItem {
+    pub class_name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub test_methods: Vec<String>,
+}
+
+impl TestItem {
+    pub fn new(class_name: &str) -> Self {
+        TestItem {
+            class_name: class_name.to_string(),
+            test_methods: vec![],
+        }
+    }
+
+    pub fn with_methods(mut self, methods: &[&str]) -> Self {
+        self.test_methods = methods.iter().map(|m| m.to_string()).collect();
+        self
+    }
+}
+
+/// Body of `runTestsAsynchronous` and `runTestsSynchronous`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources_runtestsasynchronous.htm>
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTestsRequest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TestItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_level: Option<TestLevel>,
+    /// Stop after this many failures; `-1` never stops early. Asynchronous runs only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_failed_tests: Option<i32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skip_code_coverage: bool,
+}
+
+impl RunTestsRequest {
+    /// Run every method of the named classes.
+    pub fn classes(class_names: &[&str]) -> Self {
+        RunTestsRequest {
+            tests: class_names.iter().map(|name| TestItem::new(name)).collect(),
+            test_level: Some(TestLevel::RunSpecifiedTests),
+            ..Default::default()
+        }
+    }
+
+    pub fn level(test_level: TestLevel) -> Self {
+        RunTestsRequest {
+            test_level: Some(test_level),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_test(mut self, test: TestItem) -> Self {
+        self.tests.push(test);
+        self
+    }
+
+    pub fn with_max_failed_tests(mut self, max_failed_tests: i32) -> Self {
+        self.max_failed_tests = Some(max_failed_tests);
+        self
+    }
+
+    pub fn with_skip_code_coverage(mut self, skip: bool) -> Self {
+        self.skip_code_coverage = skip;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_classes() {
+        let request = RunTestsRequest::classes(&["AccountTest"])
+            .with_test(TestItem::new("LeadTest").with_methods(&["testConvert"]))
+            .with_max_failed_tests(5);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "tests": [
+                    {"className": "AccountTest"},
+                    {"className": "LeadTest", "testMethods": ["testConvert"]}
+                ],
+                "testLevel": "RunSpecifiedTests",
+                "maxFailedTests": 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_level() {
+        let request =
+            RunTestsRequest::level(TestLevel::RunLocalTests).with_skip_code_coverage(true);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({"testLevel": "RunLocalTests", "skipCodeCoverage": true})
+        );
+    }
+}
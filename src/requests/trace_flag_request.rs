@@ -0,0 +1,147 @@
+use crate::datetime::salesforce_datetime;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// What a trace flag logs.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_traceflag.htm>
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LogType {
+    /// Logs a specific user's activity.
+    UserDebug,
+    /// Logs the current user's activity in the Developer Console.
+    DeveloperLog,
+    ClassTracing,
+}
+
+/// Creates a `TraceFlag` record through the Tooling API.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TraceFlag {
+    /// User, Apex class or trigger to log.
+    pub traced_entity_id: String,
+    pub debug_level_id: String,
+    pub log_type: LogType,
+    #[serde(with = "salesforce_datetime")]
+    pub start_date: DateTime<Utc>,
+    /// Salesforce rejects trace flags that last longer than 24 hours.
+    #[serde(with = "salesforce_datetime")]
+    pub expiration_date: DateTime<Utc>,
+}
+
+impl TraceFlag {
+    /// Log a user's activity from now until `duration` has passed.
+    pub fn for_user(user_id: &str, debug_level_id: &str, duration: Duration) -> Self {
+        let start_date = Utc::now();
+        TraceFlag {
+            traced_entity_id: user_id.to_string(),
+            debug_level_id: debug_level_id.to_string(),
+            log_type: LogType::UserDebug,
+            start_date,
+            expiration_date: start_date + duration,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    None,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Fine,
+    Finer,
+    Finest,
+}
+
+/// Creates a `DebugLevel` record, the set of log levels a trace flag refers to.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_debuglevel.htm>
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DebugLevel {
+    pub developer_name: String,
+    pub master_label: String,
+    pub apex_code: LogLevel,
+    pub apex_profiling: LogLevel,
+    pub callout: LogLevel,
+    pub database: LogLevel,
+    pub system: LogLevel,
+    pub validation: LogLevel,
+    pub visualforce: LogLevel,
+    pub workflow: LogLevel,
+}
+
+impl DebugLevel {
+    /// Apex at `DEBUG`, every other category at `INFO`.
+    pub fn new(developer_name: &str) -> Self {
+        DebugLevel {
+            developer_name: developer_name.to_string(),
+            master_label: developer_name.to_string(),
+            apex_code: LogLevel::Debug,
+            apex_profiling: LogLevel::Info,
+            callout: LogLevel::Info,
+            database: LogLevel::Info,
+            system: LogLevel::Info,
+            validation: LogLevel::Info,
+            visualforce: LogLevel::Info,
+            workflow: LogLevel::Info,
+        }
+    }
+
+    pub fn with_apex_code(mut self, level: LogLevel) -> Self {
+        self.apex_code = level;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_trace_flag() {
+        let start_date = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let flag = TraceFlag {
+            traced_entity_id: "005xx".to_string(),
+            debug_level_id: "7dlxx".to_string(),
+            log_type: LogType::UserDebug,
+            start_date,
+            expiration_date: start_date + Duration::hours(1),
+        };
+        assert_eq!(
+            serde_json::to_value(&flag).unwrap(),
+            json!({
+                "TracedEntityId": "005xx",
+                "DebugLevelId": "7dlxx",
+                "LogType": "USER_DEBUG",
+                "StartDate": "2024-01-01T00:00:00.000+0000",
+                "ExpirationDate": "2024-01-01T01:00:00.000+0000"
+            })
+        );
+    }
+
+    #[test]
+    fn test_for_user() {
+        let flag = TraceFlag::for_user("005xx", "7dlxx", Duration::minutes(30));
+        assert_eq!(flag.log_type, LogType::UserDebug);
+        assert_eq!(
+            flag.expiration_date - flag.start_date,
+            Duration::minutes(30)
+        );
+    }
+
+    #[test]
+    fn test_serialize_debug_level() {
+        let level = DebugLevel::new("Integration").with_apex_code(LogLevel::Finest);
+        let value = serde_json::to_value(&level).unwrap();
+        assert_eq!(value["DeveloperName"], "Integration");
+        assert_eq!(value["ApexCode"], "FINEST");
+        assert_eq!(value["Database"], "INFO");
+    }
+}
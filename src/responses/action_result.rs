@@ -0,0 +1,160 @@
+use crate::errors::Error;
+use crate::responses::error_response::ErrorResponse;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_action.meta/api_action/actions_obj_list.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct ActionList {
+    #[serde(default)]
+    pub actions: Vec<ActionSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionSummary {
+    pub label: String,
+    pub name: String,
+    /// e.g. `FLOW`, `APEX` or `EMAILSIMPLE`
+    #[serde(rename = "type")]
+    pub action_type: String,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Inputs and outputs of an action.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_action.meta/api_action/actions_obj_describe.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionDescribe {
+    pub name: String,
+    pub label: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub action_type: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub inputs: Vec<ActionParameter>,
+    #[serde(default)]
+    pub outputs: Vec<ActionParameter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionParameter {
+    pub name: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// e.g. `STRING`, `BOOLEAN` or `SOBJECT`
+    #[serde(rename = "type")]
+    pub parameter_type: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub sobject_type: Option<String>,
+    /// Greater than 1 for collection parameters.
+    #[serde(default)]
+    pub max_occurs: Option<u32>,
+}
+
+/// Outcome of invoking an action for one element of `inputs`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_action.meta/api_action/actions_obj_invoke.htm>
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionResult<T> {
+    pub action_name: String,
+    pub is_success: bool,
+    #[serde(default = "Option::default")]
+    pub output_values: Option<T>,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub errors: Vec<ErrorResponse>,
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+impl<T> ActionResult<T> {
+    /// The output values, or the item's errors as `Error::ErrorResponses`.
+    pub fn into_output(self) -> Result<Option<T>, Error> {
+        if self.is_success {
+            Ok(self.output_values)
+        } else {
+            Err(Error::ErrorResponses(self.errors))
+        }
+    }
+}
+
+fn null_as_empty<'de, D>(deserializer: D) -> Result<Vec<ErrorResponse>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_deserialize_describe() {
+        let describe: ActionDescribe = serde_json::from_value(json!({
+            "name": "Onboard_Customer",
+            "label": "Onboard Customer",
+            "type": "FLOW",
+            "inputs": [
+                {"name": "accountId", "type": "STRING", "required": true, "maxOccurs": 1},
+                {"name": "contacts", "type": "SOBJECT", "sobjectType": "Contact", "maxOccurs": 2000}
+            ],
+            "outputs": [{"name": "Flow__InterviewStatus", "type": "STRING"}]
+        }))
+        .unwrap();
+        assert_eq!(describe.action_type, "FLOW");
+        assert!(describe.inputs[0].required);
+        assert_eq!(describe.inputs[1].sobject_type.as_deref(), Some("Contact"));
+        assert_eq!(describe.outputs[0].name, "Flow__InterviewStatus");
+    }
+
+    #[test]
+    fn test_deserialize_results() {
+        let results: Vec<ActionResult<Value>> = serde_json::from_value(json!([
+            {
+                "actionName": "Onboard_Customer",
+                "errors": null,
+                "isSuccess": true,
+                "outputValues": {"Flow__InterviewStatus": "Finished"},
+                "version": 3
+            },
+            {
+                "actionName": "Onboard_Customer",
+                "errors": [{"statusCode": "UNKNOWN_EXCEPTION", "message": "boom", "fields": []}],
+                "isSuccess": false,
+                "outputValues": null
+            }
+        ]))
+        .unwrap();
+        assert_eq!(results[0].version, Some(3));
+        assert!(results[0].errors.is_empty());
+        assert_eq!(
+            results[0].clone().into_output().unwrap().unwrap()["Flow__InterviewStatus"],
+            "Finished"
+        );
+        assert_eq!(results[1].errors[0].error_code, "UNKNOWN_EXCEPTION");
+        assert!(matches!(
+            results[1].clone().into_output(),
+            Err(Error::ErrorResponses(_))
+        ));
+    }
+
+    #[test]
+    fn test_error_responses_are_not_results() {
+        let parsed = serde_json::from_value::<Vec<ActionResult<Value>>>(json!([
+            {"message": "The requested resource does not exist", "errorCode": "NOT_FOUND"}
+        ]));
+        assert!(parsed.is_err());
+    }
+}
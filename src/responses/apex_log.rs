@@ -0,0 +1,58 @@
+use crate::datetime::salesforce_datetime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a debug log. The log itself is downloaded separately with `ToolingApi::log_body`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apexlog.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApexLog {
+    pub id: String,
+    pub log_user_id: String,
+    /// Size of the log body in bytes.
+    pub log_length: u64,
+    /// e.g. `/apex/MyPage` or `API`.
+    pub operation: String,
+    pub request: String,
+    pub status: String,
+    #[serde(with = "salesforce_datetime")]
+    pub start_time: DateTime<Utc>,
+    pub duration_milliseconds: u64,
+    #[serde(default)]
+    pub application: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() {
+        let log: ApexLog = serde_json::from_value(json!({
+            "attributes": {"type": "ApexLog"},
+            "Id": "07Lxx",
+            "LogUserId": "005xx",
+            "LogLength": 4096,
+            "Operation": "/services/data/v60.0/sobjects/Account",
+            "Request": "Api",
+            "Status": "Success",
+            "StartTime": "2024-01-01T12:00:00.000+0000",
+            "DurationMilliseconds": 42,
+            "Application": "Unknown",
+            "Location": "Monitoring"
+        }))
+        .unwrap();
+        assert_eq!(log.id, "07Lxx");
+        assert_eq!(log.log_length, 4096);
+        assert_eq!(
+            log.start_time,
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()
+        );
+        assert_eq!(log.location.as_deref(), Some("Monitoring"));
+    }
+}
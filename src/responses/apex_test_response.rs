@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of `runTestsSynchronous`. `time` values are in milliseconds.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources_runtestssynchronous.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunTestsResult {
+    pub num_tests_run: u32,
+    pub num_failures: u32,
+    pub total_time: f64,
+    pub successes: Vec<RunTestSuccess>,
+    pub failures: Vec<RunTestFailure>,
+    pub code_coverage: Vec<CodeCoverageResult>,
+    pub apex_log_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunTestSuccess {
+    pub id: String,
+    /// Test class name.
+    pub name: String,
+    pub method_name: String,
+    pub namespace: Option<String>,
+    pub time: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunTestFailure {
+    pub id: String,
+    /// Test class name.
+    pub name: String,
+    pub method_name: String,
+    pub namespace: Option<String>,
+    pub message: String,
+    pub stack_trace: Option<String>,
+    pub time: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodeCoverageResult {
+    pub id: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub num_locations: u32,
+    pub num_locations_not_covered: u32,
+}
+
+/// Status of a test class queued by `runTestsAsynchronous`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TestQueueStatus {
+    Holding,
+    Queued,
+    Preparing,
+    Processing,
+    Completed,
+    Failed,
+    Aborted,
+    #[serde(other)]
+    #[default]
+    Unknown,
+}
+
+impl TestQueueStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            TestQueueStatus::Completed | TestQueueStatus::Failed | TestQueueStatus::Aborted
+        )
+    }
+}
+
+/// Name of a lookup, as returned for relationship fields in SOQL results.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub(crate) struct NamedRelation {
+    pub name: String,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apextestqueueitem.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub(crate) struct ApexTestQueueItem {
+    pub id: String,
+    pub apex_class_id: String,
+    pub apex_class: Option<NamedRelation>,
+    pub status: TestQueueStatus,
+    pub extended_status: Option<String>,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apextestresult.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub(crate) struct ApexTestResultRecord {
+    pub apex_class: Option<NamedRelation>,
+    pub method_name: String,
+    pub outcome: String,
+    pub message: Option<String>,
+    pub stack_trace: Option<String>,
+    /// Milliseconds.
+    pub run_time: Option<u64>,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apexcodecoverage.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub(crate) struct ApexCodeCoverageRecord {
+    pub apex_class_or_trigger_id: String,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/tooling_api_objects_apexcodecoverageaggregate.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase", default)]
+pub(crate) struct ApexCodeCoverageAggregateRecord {
+    pub apex_class_or_trigger: Option<NamedRelation>,
+    pub num_lines_covered: u32,
+    pub num_lines_uncovered: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_run_tests_result() {
+        let result: RunTestsResult = serde_json::from_value(json!({
+            "numTestsRun": 2,
+            "numFailures": 1,
+            "totalTime": 120.0,
+            "successes": [{
+                "id": "01pxx", "name": "AccountTest", "methodName": "testOk",
+                "namespace": null, "seeAllData": false, "time": 40.0
+            }],
+            "failures": [{
+                "id": "01pxx", "name": "AccountTest", "methodName": "testBad",
+                "namespace": null, "message": "System.AssertException: Assertion Failed",
+                "stackTrace": "Class.AccountTest.testBad: line 10, column 1",
+                "seeAllData": false, "time": 80.0, "type": "Class"
+            }],
+            "codeCoverage": [{
+                "id": "01pyy", "name": "AccountService", "namespace": null,
+                "numLocations": 10, "numLocationsNotCovered": 2,
+                "locationsNotCovered": [], "type": "Class"
+            }],
+            "codeCoverageWarnings": [],
+            "apexLogId": "07Lxx"
+        }))
+        .unwrap();
+        assert_eq!(result.num_failures, 1);
+        assert_eq!(result.successes[0].method_name, "testOk");
+        assert_eq!(
+            result.failures[0].stack_trace.as_deref(),
+            Some("Class.AccountTest.testBad: line 10, column 1")
+        );
+        assert_eq!(result.code_coverage[0].num_locations_not_covered, 2);
+        assert_eq!(result.apex_log_id.as_deref(), Some("07Lxx"));
+    }
+
+    #[test]
+    fn test_deserialize_queue_item() {
+        let item: ApexTestQueueItem = serde_json::from_value(json!({
+            "attributes": {"type": "ApexTestQueueItem"},
+            "Id": "709xx",
+            "ApexClassId": "01pxx",
+            "ApexClass": {"attributes": {"type": "ApexClass"}, "Name": "AccountTest"},
+            "Status": "Processing",
+            "ExtendedStatus": "(1/2)"
+        }))
+        .unwrap();
+        assert_eq!(item.status, TestQueueStatus::Processing);
+        assert!(!item.status.is_finished());
+        assert_eq!(item.apex_class.unwrap().name, "AccountTest");
+    }
+
+    #[test]
+    fn test_queue_status_finished() {
+        assert!(TestQueueStatus::Completed.is_finished());
+        assert!(TestQueueStatus::Failed.is_finished());
+        assert!(TestQueueStatus::Aborted.is_finished());
+        assert!(!TestQueueStatus::Queued.is_finished());
+        let unknown: TestQueueStatus = serde_json::from_value(json!("Later")).unwrap();
+        assert_eq!(unknown, TestQueueStatus::Unknown);
+    }
+}
@@ -0,0 +1,9 @@
+/// Details of a blob streamed by `RestApi::download_blob`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobDownload {
+    /// `Content-Type` reported by Salesforce, e.g. `application/pdf`.
+    pub content_type: Option<String>,
+    /// `Content-Length` reported by Salesforce, when the response was not chunked.
+    pub content_length: Option<u64>,
+    pub bytes_written: u64,
+}
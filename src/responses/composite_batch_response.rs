@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/responses_composite_batch.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositeBatchResponse {
+    pub has_errors: bool,
+    pub results: Vec<BatchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchResult {
+    pub status_code: u16,
+    pub result: Option<Value>,
+}
+
+impl BatchResult {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() {
+        let json_str = json!({
+            "hasErrors": true,
+            "results": [
+                {"statusCode": 200, "result": {"DailyApiRequests": {"Max": 15000, "Remaining": 14998}}},
+                {"statusCode": 204, "result": null},
+                {"statusCode": 404, "result": [{"errorCode": "NOT_FOUND", "message": "missing"}]}
+            ]
+        })
+        .to_string();
+
+        let resp: CompositeBatchResponse = serde_json::from_str(&json_str).unwrap();
+        assert!(resp.has_errors);
+        assert_eq!(resp.results.len(), 3);
+        assert!(resp.results[0].is_success());
+        assert_eq!(
+            resp.results[0].result.as_ref().unwrap()["DailyApiRequests"]["Max"],
+            15000
+        );
+        assert!(resp.results[1].is_success());
+        assert_eq!(resp.results[1].result, None);
+        assert!(!resp.results[2].is_success());
+    }
+}
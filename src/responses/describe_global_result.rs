@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_describeGlobal.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DescribeGlobalResult {
+    pub encoding: String,
+    pub max_batch_size: u32,
+    pub sobjects: Vec<DescribeGlobalSObjectResult>,
+}
+
+pub type DescribeGlobalResponse = DescribeGlobalResult;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DescribeGlobalSObjectResult {
+    pub name: String,
+    pub label: String,
+    pub label_plural: String,
+    pub key_prefix: Option<String>,
+    pub custom: bool,
+    pub custom_setting: bool,
+    pub createable: bool,
+    pub updateable: bool,
+    pub deletable: bool,
+    pub undeletable: bool,
+    pub mergeable: bool,
+    pub queryable: bool,
+    pub searchable: bool,
+    pub retrieveable: bool,
+    pub replicateable: bool,
+    pub layoutable: bool,
+    pub triggerable: bool,
+    pub feed_enabled: bool,
+    pub urls: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() {
+        let json_str = json!({
+            "encoding": "UTF-8",
+            "maxBatchSize": 200,
+            "sobjects": [
+                {
+                    "name": "Account",
+                    "label": "Account",
+                    "labelPlural": "Accounts",
+                    "keyPrefix": "001",
+                    "custom": false,
+                    "queryable": true,
+                    "associateEntityType": null,
+                    "urls": {"describe": "/services/data/v60.0/sobjects/Account/describe"}
+                },
+                {"name": "AccountChangeEvent", "keyPrefix": null}
+            ]
+        })
+        .to_string();
+
+        let resp: DescribeGlobalResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.encoding, "UTF-8");
+        assert_eq!(resp.max_batch_size, 200);
+        assert_eq!(resp.sobjects.len(), 2);
+        assert_eq!(resp.sobjects[0].key_prefix, Some("001".to_string()));
+        assert!(resp.sobjects[0].queryable);
+        assert_eq!(resp.sobjects[1].key_prefix, None);
+    }
+}
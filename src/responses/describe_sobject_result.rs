@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Metadata for a single sObject.
+///
+/// Every field is defaulted so that payloads from older or newer API versions
+/// still deserialize; properties not modelled here are ignored.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_describe.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DescribeSObjectResult {
+    pub name: String,
+    pub label: String,
+    pub label_plural: String,
+    pub key_prefix: Option<String>,
+    pub custom: bool,
+    pub custom_setting: bool,
+    pub createable: bool,
+    pub updateable: bool,
+    pub deletable: bool,
+    pub undeletable: bool,
+    pub mergeable: bool,
+    pub queryable: bool,
+    pub searchable: bool,
+    pub retrieveable: bool,
+    pub replicateable: bool,
+    pub layoutable: bool,
+    pub triggerable: bool,
+    pub feed_enabled: bool,
+    pub fields: Vec<Field>,
+    pub child_relationships: Vec<ChildRelationship>,
+    pub record_type_infos: Vec<RecordTypeInfo>,
+    pub urls: HashMap<String, String>,
+}
+
+pub type DescribeResponse = DescribeSObjectResult;
+
+impl DescribeSObjectResult {
+    /// Look up a field by API name, ignoring case as Salesforce does.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Field {
+    pub name: String,
+    pub label: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    pub soap_type: String,
+    pub length: u32,
+    pub byte_length: u32,
+    pub precision: u32,
+    pub scale: u32,
+    pub digits: u32,
+    pub custom: bool,
+    pub nillable: bool,
+    pub createable: bool,
+    pub updateable: bool,
+    pub unique: bool,
+    pub external_id: bool,
+    pub id_lookup: bool,
+    pub name_field: bool,
+    pub auto_number: bool,
+    pub calculated: bool,
+    pub calculated_formula: Option<String>,
+    pub case_sensitive: bool,
+    pub encrypted: bool,
+    pub html_formatted: bool,
+    pub defaulted_on_create: bool,
+    pub default_value: Option<Value>,
+    pub inline_help_text: Option<String>,
+    pub filterable: bool,
+    pub groupable: bool,
+    pub sortable: bool,
+    pub picklist_values: Vec<PicklistValue>,
+    pub dependent_picklist: bool,
+    pub controller_name: Option<String>,
+    pub reference_to: Vec<String>,
+    pub relationship_name: Option<String>,
+    pub relationship_order: Option<u32>,
+    pub polymorphic_foreign_key: bool,
+    pub cascade_delete: bool,
+    pub restricted_delete: bool,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/field_types.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Id,
+    Boolean,
+    String,
+    TextArea,
+    EncryptedString,
+    Email,
+    Phone,
+    Url,
+    Picklist,
+    MultiPicklist,
+    Combobox,
+    Reference,
+    Int,
+    Long,
+    Double,
+    Currency,
+    Percent,
+    Date,
+    DateTime,
+    Time,
+    Base64,
+    Address,
+    Location,
+    #[serde(rename = "anyType")]
+    AnyType,
+    ComplexValue,
+    DataCategoryGroupReference,
+    /// A type introduced after this crate was released.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PicklistValue {
+    pub active: bool,
+    pub default_value: bool,
+    pub label: Option<String>,
+    pub value: String,
+    /// Base64 bitmap of the controlling field values this entry is valid for.
+    pub valid_for: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChildRelationship {
+    pub child_s_object: String,
+    pub field: String,
+    pub relationship_name: Option<String>,
+    pub cascade_delete: bool,
+    pub restricted_delete: bool,
+    pub deprecated_and_hidden: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordTypeInfo {
+    pub name: String,
+    pub developer_name: String,
+    pub record_type_id: String,
+    pub active: bool,
+    pub available: bool,
+    pub default_record_type_mapping: bool,
+    pub master: bool,
+    pub urls: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize() {
+        let json_str = json!({
+            "name": "Contact",
+            "label": "Contact",
+            "labelPlural": "Contacts",
+            "keyPrefix": "003",
+            "createable": true,
+            "someFutureProperty": {"nested": true},
+            "fields": [
+                {
+                    "name": "Id",
+                    "type": "id",
+                    "soapType": "tns:ID",
+                    "length": 18,
+                    "idLookup": true
+                },
+                {
+                    "name": "AccountId",
+                    "type": "reference",
+                    "referenceTo": ["Account"],
+                    "relationshipName": "Account",
+                    "nillable": true
+                },
+                {
+                    "name": "LeadSource",
+                    "type": "picklist",
+                    "picklistValues": [
+                        {"active": true, "defaultValue": false, "label": "Web", "value": "Web", "validFor": null}
+                    ]
+                },
+                {"name": "Birthdate", "type": "date"},
+                {"name": "LastModifiedDate", "type": "datetime"},
+                {"name": "Data__c", "type": "anyType"},
+                {"name": "Vector__c", "type": "vector"}
+            ],
+            "childRelationships": [
+                {"childSObject": "Case", "field": "ContactId", "relationshipName": "Cases", "cascadeDelete": false}
+            ],
+            "recordTypeInfos": [
+                {
+                    "name": "Master",
+                    "developerName": "Master",
+                    "recordTypeId": "012000000000000AAA",
+                    "active": true,
+                    "available": true,
+                    "defaultRecordTypeMapping": true,
+                    "master": true,
+                    "urls": {"layout": "/services/data/v60.0/sobjects/Contact/describe/layouts/012000000000000AAA"}
+                }
+            ],
+            "urls": {"sobject": "/services/data/v60.0/sobjects/Contact"}
+        })
+        .to_string();
+
+        let resp: DescribeSObjectResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.name, "Contact");
+        assert_eq!(resp.key_prefix, Some("003".to_string()));
+        assert!(resp.createable);
+        assert!(!resp.custom);
+        assert_eq!(resp.fields.len(), 7);
+        assert_eq!(resp.fields[0].field_type, FieldType::Id);
+        assert!(resp.fields[0].id_lookup);
+        assert_eq!(resp.fields[1].reference_to, vec!["Account".to_string()]);
+        assert_eq!(
+            resp.fields[1].relationship_name,
+            Some("Account".to_string())
+        );
+        assert_eq!(resp.fields[2].picklist_values[0].value, "Web");
+        assert_eq!(resp.fields[3].field_type, FieldType::Date);
+        assert_eq!(resp.fields[4].field_type, FieldType::DateTime);
+        assert_eq!(resp.fields[5].field_type, FieldType::AnyType);
+        assert_eq!(resp.fields[6].field_type, FieldType::Unknown);
+        assert_eq!(resp.child_relationships[0].child_s_object, "Case");
+        assert!(resp.record_type_infos[0].master);
+        assert_eq!(
+            resp.urls["sobject"],
+            "/services/data/v60.0/sobjects/Contact"
+        );
+    }
+
+    #[test]
+    fn test_field_lookup_is_case_insensitive() {
+        let resp = DescribeSObjectResult {
+            fields: vec![Field {
+                name: "AccountId".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(resp.field("accountid").is_some());
+        assert!(resp.field("Missing").is_none());
+    }
+}
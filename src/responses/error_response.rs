@@ -1,13 +1,34 @@
+use crate::xml::{child_string, children_named};
+use roxmltree::Node;
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponse {
     pub message: String,
+    #[serde(alias = "statusCode")]
     pub error_code: String,
     pub fields: Option<Vec<String>>,
 }
 
+impl ErrorResponse {
+    /// An `<errors>` element of a SOAP result.
+    pub(crate) fn from_xml(node: Node) -> Self {
+        let fields: Vec<String> = children_named(node, "fields")
+            .map(|f| f.text().unwrap_or_default().to_string())
+            .collect();
+        ErrorResponse {
+            message: child_string(node, "message").unwrap_or_default(),
+            error_code: child_string(node, "statusCode").unwrap_or_default(),
+            fields: if fields.is_empty() {
+                None
+            } else {
+                Some(fields)
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +61,19 @@ mod tests {
         assert_eq!(resp.fields, None);
     }
 
+    #[test]
+    fn test_status_code_alias() {
+        let json_str = json!({
+            "message": "Required fields are missing: [Name]",
+            "statusCode": "REQUIRED_FIELD_MISSING",
+            "fields": ["Name"]
+        })
+        .to_string();
+
+        let resp: ErrorResponse = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.error_code, "REQUIRED_FIELD_MISSING");
+    }
+
     #[test]
     fn test_default() {
         let resp = ErrorResponse::default();
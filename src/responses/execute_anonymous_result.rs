@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of running anonymous Apex. `compiled` and `success` tell compile errors
+/// apart from runtime exceptions.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_tooling.meta/api_tooling/intro_rest_resources.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExecuteAnonymousResult {
+    pub compiled: bool,
+    pub success: bool,
+    /// Line of the compile problem or exception, -1 when there is none.
+    pub line: i32,
+    pub column: i32,
+    pub compile_problem: Option<String>,
+    pub exception_message: Option<String>,
+    pub exception_stack_trace: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_compile_error() {
+        let result: ExecuteAnonymousResult = serde_json::from_value(json!({
+            "line": 1,
+            "column": 13,
+            "compiled": false,
+            "success": false,
+            "compileProblem": "Unexpected token '('.",
+            "exceptionStackTrace": null,
+            "exceptionMessage": null
+        }))
+        .unwrap();
+        assert!(!result.compiled);
+        assert_eq!(result.column, 13);
+        assert_eq!(
+            result.compile_problem.as_deref(),
+            Some("Unexpected token '('.")
+        );
+        assert!(result.exception_message.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_exception() {
+        let result: ExecuteAnonymousResult = serde_json::from_value(json!({
+            "line": 1,
+            "column": 1,
+            "compiled": true,
+            "success": false,
+            "compileProblem": null,
+            "exceptionStackTrace": "AnonymousBlock: line 1, column 1",
+            "exceptionMessage": "System.MathException: Divide by 0"
+        }))
+        .unwrap();
+        assert!(result.compiled);
+        assert!(!result.success);
+        assert_eq!(
+            result.exception_message.as_deref(),
+            Some("System.MathException: Divide by 0")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+/// Query plans Salesforce considered for a SOQL query, cheapest first.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/dome_query_explain.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExplainResult {
+    pub plans: Vec<ExplainPlan>,
+    pub source_query: String,
+}
+
+impl ExplainResult {
+    /// The plan Salesforce will use, i.e. the one with the lowest relative cost.
+    pub fn best_plan(&self) -> Option<&ExplainPlan> {
+        self.plans
+            .iter()
+            .min_by(|a, b| a.relative_cost.total_cmp(&b.relative_cost))
+    }
+
+    /// Logs a warning and returns `true` when the best plan scans the whole table,
+    /// i.e. the query is not selective.
+    pub fn warn_if_table_scan(&self) -> bool {
+        match self.best_plan() {
+            Some(plan) if plan.leading_operation_type == LeadingOperationType::TableScan => {
+                log::warn!(
+                    "Query on {} is not selective: TableScan over {} records (relative cost {}). {}",
+                    plan.sobject_type,
+                    plan.sobject_cardinality,
+                    plan.relative_cost,
+                    self.source_query
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExplainPlan {
+    /// Estimated number of records the leading operation returns.
+    pub cardinality: u64,
+    /// Indexed fields used by the plan; empty for a TableScan.
+    pub fields: Vec<String>,
+    pub leading_operation_type: LeadingOperationType,
+    pub notes: Vec<PlanNote>,
+    /// Cost compared to the selectivity threshold; above 1.0 the query is not selective.
+    pub relative_cost: f64,
+    /// Approximate record count of the queried object.
+    pub sobject_cardinality: u64,
+    pub sobject_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeadingOperationType {
+    Index,
+    Other,
+    Sharing,
+    TableScan,
+    #[serde(other)]
+    #[default]
+    Unknown,
+}
+
+/// Why an optimization wasn't used, e.g. a filter on an unindexed field.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlanNote {
+    pub description: String,
+    pub fields: Vec<String>,
+    pub table_enum_or_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> ExplainResult {
+        serde_json::from_value(json!({
+            "plans": [
+                {
+                    "cardinality": 2843,
+                    "fields": [],
+                    "leadingOperationType": "TableScan",
+                    "notes": [{
+                        "description": "Not considering filter for optimization because unindexed",
+                        "fields": ["Industry"],
+                        "tableEnumOrId": "Account"
+                    }],
+                    "relativeCost": 1.9,
+                    "sobjectCardinality": 2843,
+                    "sobjectType": "Account"
+                },
+                {
+                    "cardinality": 2843,
+                    "fields": [],
+                    "leadingOperationType": "Sharing",
+                    "notes": [],
+                    "relativeCost": 2.4,
+                    "sobjectCardinality": 2843,
+                    "sobjectType": "Account"
+                }
+            ],
+            "sourceQuery": "SELECT Id FROM Account WHERE Industry = 'Tech'"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let result = sample();
+        assert_eq!(result.plans.len(), 2);
+        let plan = &result.plans[0];
+        assert_eq!(plan.leading_operation_type, LeadingOperationType::TableScan);
+        assert_eq!(plan.cardinality, 2843);
+        assert_eq!(plan.sobject_type, "Account");
+        assert_eq!(plan.notes[0].fields, vec!["Industry"]);
+        assert_eq!(plan.notes[0].table_enum_or_id, "Account");
+    }
+
+    #[test]
+    fn test_best_plan_and_table_scan_warning() {
+        let mut result = sample();
+        assert_eq!(result.best_plan().unwrap().relative_cost, 1.9);
+        assert!(result.warn_if_table_scan());
+
+        result.plans[0].relative_cost = 3.0;
+        assert_eq!(
+            result.best_plan().unwrap().leading_operation_type,
+            LeadingOperationType::Sharing
+        );
+        assert!(!result.warn_if_table_scan());
+    }
+
+    #[test]
+    fn test_unknown_operation_type_and_no_plans() {
+        let plan: ExplainPlan =
+            serde_json::from_value(json!({"leadingOperationType": "Something"})).unwrap();
+        assert_eq!(plan.leading_operation_type, LeadingOperationType::Unknown);
+        assert!(ExplainResult::default().best_plan().is_none());
+        assert!(!ExplainResult::default().warn_if_table_scan());
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Org limits keyed by name, e.g. `DailyApiRequests` or `DailyBulkV2QueryJobs`.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_limits.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct OrgLimits(pub HashMap<String, Limit>);
+
+impl OrgLimits {
+    pub fn get(&self, name: &str) -> Option<&Limit> {
+        self.0.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Limit)> {
+        self.0.iter()
+    }
+
+    /// `true` when the named limit has less than `fraction` (0.0 - 1.0) of its maximum remaining.
+    /// Unknown limits are never considered low.
+    pub fn is_low(&self, name: &str, fraction: f64) -> bool {
+        self.get(name)
+            .map(|limit| limit.remaining_fraction() < fraction)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Limit {
+    #[serde(rename = "Max")]
+    pub max: i64,
+    #[serde(rename = "Remaining")]
+    pub remaining: i64,
+    /// Some limits are broken down further, e.g. API requests per connected app.
+    #[serde(flatten)]
+    pub breakdown: HashMap<String, Limit>,
+}
+
+impl Limit {
+    pub fn used(&self) -> i64 {
+        self.max - self.remaining
+    }
+
+    /// Share of the limit still available. A limit with no maximum counts as fully available.
+    pub fn remaining_fraction(&self) -> f64 {
+        if self.max <= 0 {
+            1.0
+        } else {
+            self.remaining as f64 / self.max as f64
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_record_count.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct RecordCountResult {
+    #[serde(rename = "sObjects")]
+    pub s_objects: Vec<RecordCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RecordCount {
+    pub name: String,
+    pub count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_limits() {
+        let json_str = json!({
+            "DailyApiRequests": {
+                "Max": 15000,
+                "Remaining": 1000,
+                "Ant Migration Tool": {"Max": 0, "Remaining": 0}
+            },
+            "DailyBulkV2QueryJobs": {"Max": 10000, "Remaining": 9999}
+        })
+        .to_string();
+
+        let limits: OrgLimits = serde_json::from_str(&json_str).unwrap();
+        let api = limits.get("DailyApiRequests").unwrap();
+        assert_eq!(api.max, 15000);
+        assert_eq!(api.remaining, 1000);
+        assert_eq!(api.used(), 14000);
+        assert_eq!(api.breakdown["Ant Migration Tool"].max, 0);
+        assert!(limits.is_low("DailyApiRequests", 0.1));
+        assert!(!limits.is_low("DailyBulkV2QueryJobs", 0.1));
+        assert!(!limits.is_low("Missing", 0.1));
+    }
+
+    #[test]
+    fn test_remaining_fraction_without_max() {
+        let limit = Limit::default();
+        assert_eq!(limit.remaining_fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_deserialize_record_count() {
+        let json_str = json!({
+            "sObjects": [{"count": 3, "name": "Account"}, {"count": 10, "name": "Contact"}]
+        })
+        .to_string();
+
+        let result: RecordCountResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(result.s_objects.len(), 2);
+        assert_eq!(result.s_objects[1].name, "Contact");
+        assert_eq!(result.s_objects[1].count, 10);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::responses::apex_test_response::RunTestFailure;
+use crate::responses::error_response::ErrorResponse;
+use crate::xml::{child_bool, child_parse, child_string, children_named};
+use roxmltree::Node;
+
+/// Handle of a queued deploy or retrieve.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_asyncresult.htm>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsyncResult {
+    pub id: String,
+    pub done: bool,
+}
+
+impl AsyncResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        AsyncResult {
+            id: child_string(node, "id").unwrap_or_default(),
+            done: child_bool(node, "done"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeployStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Succeeded,
+    SucceededPartial,
+    Failed,
+    Canceling,
+    Canceled,
+}
+
+impl DeployStatus {
+    fn parse(value: &str) -> DeployStatus {
+        match value {
+            "InProgress" => DeployStatus::InProgress,
+            "Succeeded" => DeployStatus::Succeeded,
+            "SucceededPartial" => DeployStatus::SucceededPartial,
+            "Failed" => DeployStatus::Failed,
+            "Canceling" => DeployStatus::Canceling,
+            "Canceled" => DeployStatus::Canceled,
+            _ => DeployStatus::Pending,
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_deployresult.htm>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeployResult {
+    pub id: String,
+    pub done: bool,
+    pub status: DeployStatus,
+    pub success: bool,
+    pub check_only: bool,
+    pub number_components_total: u32,
+    pub number_components_deployed: u32,
+    pub number_component_errors: u32,
+    pub number_tests_total: u32,
+    pub number_tests_completed: u32,
+    pub number_test_errors: u32,
+    pub error_message: Option<String>,
+    pub error_status_code: Option<String>,
+    pub state_detail: Option<String>,
+    /// Only filled when the status was requested with details.
+    pub component_failures: Vec<DeployMessage>,
+    pub test_failures: Vec<RunTestFailure>,
+}
+
+impl DeployResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        let details = node.children().find(|n| n.has_tag_name("details"));
+        let component_failures = details
+            .map(|d| {
+                children_named(d, "componentFailures")
+                    .map(DeployMessage::from_xml)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let test_failures = details
+            .and_then(|d| d.children().find(|n| n.has_tag_name("runTestResult")))
+            .map(|r| {
+                children_named(r, "failures")
+                    .map(|f| RunTestFailure {
+                        id: child_string(f, "id").unwrap_or_default(),
+                        name: child_string(f, "name").unwrap_or_default(),
+                        method_name: child_string(f, "methodName").unwrap_or_default(),
+                        namespace: child_string(f, "namespace").filter(|n| !n.is_empty()),
+                        message: child_string(f, "message").unwrap_or_default(),
+                        stack_trace: child_string(f, "stackTrace"),
+                        time: child_parse(f, "time").unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        DeployResult {
+            id: child_string(node, "id").unwrap_or_default(),
+            done: child_bool(node, "done"),
+            status: DeployStatus::parse(&child_string(node, "status").unwrap_or_default()),
+            success: child_bool(node, "success"),
+            check_only: child_bool(node, "checkOnly"),
+            number_components_total: child_parse(node, "numberComponentsTotal").unwrap_or(0),
+            number_components_deployed: child_parse(node, "numberComponentsDeployed").unwrap_or(0),
+            number_component_errors: child_parse(node, "numberComponentErrors").unwrap_or(0),
+            number_tests_total: child_parse(node, "numberTestsTotal").unwrap_or(0),
+            number_tests_completed: child_parse(node, "numberTestsCompleted").unwrap_or(0),
+            number_test_errors: child_parse(node, "numberTestErrors").unwrap_or(0),
+            error_message: child_string(node, "errorMessage"),
+            error_status_code: child_string(node, "errorStatusCode"),
+            state_detail: child_string(node, "stateDetail"),
+            component_failures,
+            test_failures,
+        }
+    }
+}
+
+/// A component that failed to deploy.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_deployresult.htm#deploymessage>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeployMessage {
+    pub component_type: String,
+    pub full_name: String,
+    pub file_name: String,
+    pub problem: String,
+    /// `Error` or `Warning`.
+    pub problem_type: String,
+    pub line_number: Option<u32>,
+    pub column_number: Option<u32>,
+}
+
+impl DeployMessage {
+    fn from_xml(node: Node) -> Self {
+        DeployMessage {
+            component_type: child_string(node, "componentType").unwrap_or_default(),
+            full_name: child_string(node, "fullName").unwrap_or_default(),
+            file_name: child_string(node, "fileName").unwrap_or_default(),
+            problem: child_string(node, "problem").unwrap_or_default(),
+            problem_type: child_string(node, "problemType").unwrap_or_default(),
+            line_number: child_parse(node, "lineNumber"),
+            column_number: child_parse(node, "columnNumber"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetrieveStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+impl RetrieveStatus {
+    fn parse(value: &str) -> RetrieveStatus {
+        match value {
+            "InProgress" => RetrieveStatus::InProgress,
+            "Succeeded" => RetrieveStatus::Succeeded,
+            "Failed" => RetrieveStatus::Failed,
+            _ => RetrieveStatus::Pending,
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_retrieveresult.htm>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetrieveResult {
+    pub id: String,
+    pub done: bool,
+    pub status: RetrieveStatus,
+    pub success: bool,
+    /// The retrieved components as a zip archive, once the retrieve succeeded.
+    pub zip_file: Option<Vec<u8>>,
+    pub file_properties: Vec<FileProperties>,
+    /// Problems with individual components, e.g. a member that does not exist.
+    pub messages: Vec<RetrieveMessage>,
+    pub error_message: Option<String>,
+    pub error_status_code: Option<String>,
+}
+
+impl RetrieveResult {
+    pub(crate) fn from_xml(node: Node) -> Result<Self, base64::DecodeError> {
+        use base64::Engine;
+
+        let zip_file = match child_string(node, "zipFile").filter(|z| !z.is_empty()) {
+            Some(encoded) => {
+                let compact: String = encoded.split_whitespace().collect();
+                Some(base64::engine::general_purpose::STANDARD.decode(compact)?)
+            }
+            None => None,
+        };
+        Ok(RetrieveResult {
+            id: child_string(node, "id").unwrap_or_default(),
+            done: child_bool(node, "done"),
+            status: RetrieveStatus::parse(&child_string(node, "status").unwrap_or_default()),
+            success: child_bool(node, "success"),
+            zip_file,
+            file_properties: children_named(node, "fileProperties")
+                .map(FileProperties::from_xml)
+                .collect(),
+            messages: children_named(node, "messages")
+                .map(|m| RetrieveMessage {
+                    file_name: child_string(m, "fileName").unwrap_or_default(),
+                    problem: child_string(m, "problem").unwrap_or_default(),
+                })
+                .collect(),
+            error_message: child_string(node, "errorMessage"),
+            error_status_code: child_string(node, "errorStatusCode"),
+        })
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_retrieveresult.htm#fileproperties>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileProperties {
+    pub file_name: String,
+    pub full_name: String,
+    pub id: String,
+    /// Metadata type, e.g. `ApexClass`.
+    pub type_name: String,
+    pub namespace_prefix: Option<String>,
+    pub last_modified_by_name: Option<String>,
+    pub last_modified_date: Option<String>,
+}
+
+impl FileProperties {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        FileProperties {
+            file_name: child_string(node, "fileName").unwrap_or_default(),
+            full_name: child_string(node, "fullName").unwrap_or_default(),
+            id: child_string(node, "id").unwrap_or_default(),
+            type_name: child_string(node, "type").unwrap_or_default(),
+            namespace_prefix: child_string(node, "namespacePrefix").filter(|n| !n.is_empty()),
+            last_modified_by_name: child_string(node, "lastModifiedByName"),
+            last_modified_date: child_string(node, "lastModifiedDate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetrieveMessage {
+    pub file_name: String,
+    pub problem: String,
+}
+
+/// Outcome for one component of a create, update, upsert, delete or rename call.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_meta.meta/api_meta/meta_saveresult.htm>
+#[derive(Debug, Clone, Default)]
+pub struct MetadataSaveResult {
+    pub full_name: String,
+    pub success: bool,
+    /// `true` when an upsert created the component, `false` when it updated one.
+    pub created: bool,
+    pub errors: Vec<ErrorResponse>,
+}
+
+impl MetadataSaveResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        MetadataSaveResult {
+            full_name: child_string(node, "fullName").unwrap_or_default(),
+            success: child_bool(node, "success"),
+            created: child_bool(node, "created"),
+            errors: children_named(node, "errors")
+                .map(ErrorResponse::from_xml)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roxmltree::Document;
+
+    #[test]
+    fn test_deploy_result_with_failures() {
+        let xml = r#"<result xmlns="http://soap.sforce.com/2006/04/metadata">
+            <checkOnly>true</checkOnly>
+            <details>
+                <componentFailures>
+                    <columnNumber>5</columnNumber>
+                    <componentType>ApexClass</componentType>
+                    <fileName>classes/Broken.cls</fileName>
+                    <fullName>Broken</fullName>
+                    <lineNumber>12</lineNumber>
+                    <problem>Unexpected token ';'.</problem>
+                    <problemType>Error</problemType>
+                </componentFailures>
+                <runTestResult>
+                    <failures>
+                        <message>System.AssertException: Assertion Failed</message>
+                        <methodName>testIt</methodName>
+                        <name>BrokenTest</name>
+                        <namespace xsi:nil="true" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"/>
+                        <stackTrace>Class.BrokenTest.testIt: line 4</stackTrace>
+                        <time>12.0</time>
+                    </failures>
+                    <numFailures>1</numFailures>
+                </runTestResult>
+            </details>
+            <done>true</done>
+            <id>0Afxx</id>
+            <numberComponentErrors>1</numberComponentErrors>
+            <numberComponentsDeployed>3</numberComponentsDeployed>
+            <numberComponentsTotal>4</numberComponentsTotal>
+            <numberTestErrors>1</numberTestErrors>
+            <numberTestsCompleted>2</numberTestsCompleted>
+            <numberTestsTotal>3</numberTestsTotal>
+            <status>Failed</status>
+            <success>false</success>
+        </result>"#;
+        let doc = Document::parse(xml).unwrap();
+        let result = DeployResult::from_xml(doc.root_element());
+
+        assert_eq!(result.id, "0Afxx");
+        assert!(result.done);
+        assert!(result.check_only);
+        assert_eq!(result.status, DeployStatus::Failed);
+        assert_eq!(result.number_components_total, 4);
+        assert_eq!(result.number_test_errors, 1);
+
+        let failure = &result.component_failures[0];
+        assert_eq!(failure.full_name, "Broken");
+        assert_eq!(failure.line_number, Some(12));
+        assert_eq!(failure.problem, "Unexpected token ';'.");
+
+        let test = &result.test_failures[0];
+        assert_eq!(test.name, "BrokenTest");
+        assert_eq!(test.namespace, None);
+        assert_eq!(
+            test.stack_trace.as_deref(),
+            Some("Class.BrokenTest.testIt: line 4")
+        );
+        assert_eq!(test.time, 12.0);
+    }
+
+    #[test]
+    fn test_retrieve_result() {
+        let xml = r#"<result>
+            <done>true</done>
+            <fileProperties>
+                <fileName>classes/MyClass.cls</fileName>
+                <fullName>MyClass</fullName>
+                <id>01pxx</id>
+                <type>ApexClass</type>
+            </fileProperties>
+            <id>09Sxx</id>
+            <messages>
+                <fileName>package.xml</fileName>
+                <problem>Entity of type 'ApexClass' named 'Missing' cannot be found</problem>
+            </messages>
+            <status>Succeeded</status>
+            <success>true</success>
+            <zipFile>UEsDBA==</zipFile>
+        </result>"#;
+        let doc = Document::parse(xml).unwrap();
+        let result = RetrieveResult::from_xml(doc.root_element()).unwrap();
+
+        assert_eq!(result.status, RetrieveStatus::Succeeded);
+        assert_eq!(result.zip_file, Some(b"PK\x03\x04".to_vec()));
+        assert_eq!(result.file_properties[0].type_name, "ApexClass");
+        assert_eq!(result.messages[0].file_name, "package.xml");
+    }
+
+    #[test]
+    fn test_retrieve_result_invalid_zip() {
+        let doc = Document::parse("<result><zipFile>not base64!</zipFile></result>").unwrap();
+        assert!(RetrieveResult::from_xml(doc.root_element()).is_err());
+    }
+
+    #[test]
+    fn test_metadata_save_result() {
+        let xml = r#"<result>
+            <errors>
+                <fields>type</fields>
+                <message>Must specify a non-empty label</message>
+                <statusCode>FIELD_INTEGRITY_EXCEPTION</statusCode>
+            </errors>
+            <fullName>Account.Tier__c</fullName>
+            <success>false</success>
+        </result>"#;
+        let doc = Document::parse(xml).unwrap();
+        let result = MetadataSaveResult::from_xml(doc.root_element());
+
+        assert_eq!(result.full_name, "Account.Tier__c");
+        assert!(!result.success);
+        assert!(!result.created);
+        assert_eq!(result.errors[0].error_code, "FIELD_INTEGRITY_EXCEPTION");
+        assert_eq!(result.errors[0].fields, Some(vec!["type".to_string()]));
+    }
+
+    #[test]
+    fn test_async_result() {
+        let doc = Document::parse("<result><done>false</done><id>0Afxx</id></result>").unwrap();
+        let result = AsyncResult::from_xml(doc.root_element());
+        assert_eq!(result.id, "0Afxx");
+        assert!(!result.done);
+    }
+}
@@ -1,3 +1,20 @@
+pub mod action_result;
+pub mod apex_log;
+pub mod apex_test_response;
+pub mod blob_download;
+pub mod composite_batch_response;
+pub mod describe_global_result;
+pub mod describe_sobject_result;
 pub mod error_response;
+pub mod execute_anonymous_result;
+pub mod explain_response;
+pub mod limits_response;
+pub mod metadata_result;
+pub mod partner_result;
+pub mod publish_result;
+pub mod query_result;
+pub mod replication_response;
+pub mod save_result;
+pub mod soap_fault;
 pub mod token_error_response;
-pub mod token_response;
\ No newline at end of file
+pub mod token_response;
@@ -0,0 +1,203 @@
+use crate::responses::error_response::ErrorResponse;
+use crate::xml::{child_bool, child_parse, child_string, children_named};
+use roxmltree::Node;
+
+fn errors(node: Node) -> Vec<ErrorResponse> {
+    children_named(node, "errors")
+        .map(ErrorResponse::from_xml)
+        .collect()
+}
+
+fn strings(node: Node, name: &str) -> Vec<String> {
+    children_named(node, name)
+        .map(|n| n.text().unwrap_or_default().to_string())
+        .collect()
+}
+
+/// Empty ID elements are sent as `xsi:nil`.
+fn optional_id(node: Node, name: &str) -> Option<String> {
+    child_string(node, name).filter(|id| !id.is_empty())
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_merge_mergeresult.htm>
+#[derive(Debug, Clone, Default)]
+pub struct MergeResult {
+    /// The master record.
+    pub id: String,
+    pub success: bool,
+    /// Records merged into the master and deleted.
+    pub merged_record_ids: Vec<String>,
+    /// Child records reparented to the master.
+    pub updated_related_ids: Vec<String>,
+    pub errors: Vec<ErrorResponse>,
+}
+
+impl MergeResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        MergeResult {
+            id: child_string(node, "id").unwrap_or_default(),
+            success: child_bool(node, "success"),
+            merged_record_ids: strings(node, "mergedRecordIds"),
+            updated_related_ids: strings(node, "updatedRelatedIds"),
+            errors: errors(node),
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_convertlead.htm#leadconvertresult>
+#[derive(Debug, Clone, Default)]
+pub struct LeadConvertResult {
+    pub lead_id: String,
+    pub success: bool,
+    pub account_id: Option<String>,
+    pub contact_id: Option<String>,
+    /// Not set when the conversion skipped the opportunity.
+    pub opportunity_id: Option<String>,
+    pub errors: Vec<ErrorResponse>,
+}
+
+impl LeadConvertResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        LeadConvertResult {
+            lead_id: child_string(node, "leadId").unwrap_or_default(),
+            success: child_bool(node, "success"),
+            account_id: optional_id(node, "accountId"),
+            contact_id: optional_id(node, "contactId"),
+            opportunity_id: optional_id(node, "opportunityId"),
+            errors: errors(node),
+        }
+    }
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_process_processresult.htm>
+#[derive(Debug, Clone, Default)]
+pub struct ProcessResult {
+    /// The record submitted for approval.
+    pub entity_id: String,
+    pub success: bool,
+    /// `ProcessInstance` ID.
+    pub instance_id: Option<String>,
+    /// `Approved`, `Rejected`, `Removed` or `Pending`.
+    pub instance_status: Option<String>,
+    /// Users or queues the approval is now assigned to.
+    pub actor_ids: Vec<String>,
+    pub new_workitem_ids: Vec<String>,
+    pub errors: Vec<ErrorResponse>,
+}
+
+impl ProcessResult {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        ProcessResult {
+            entity_id: child_string(node, "entityId").unwrap_or_default(),
+            success: child_bool(node, "success"),
+            instance_id: optional_id(node, "instanceId"),
+            instance_status: child_string(node, "instanceStatus"),
+            actor_ids: strings(node, "actorIds"),
+            new_workitem_ids: strings(node, "newWorkitemIds"),
+            errors: errors(node),
+        }
+    }
+}
+
+/// The logged-in user and their organization.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_getuserinfo_getuserinforesult.htm>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserInfo {
+    pub user_id: String,
+    pub user_name: String,
+    pub user_full_name: String,
+    pub user_email: String,
+    pub organization_id: String,
+    pub organization_name: String,
+    pub organization_multi_currency: bool,
+    pub profile_id: String,
+    pub role_id: Option<String>,
+    pub user_language: String,
+    pub user_locale: String,
+    pub user_time_zone: String,
+    pub user_type: String,
+    pub currency_symbol: Option<String>,
+    pub session_seconds_valid: u64,
+}
+
+impl UserInfo {
+    pub(crate) fn from_xml(node: Node) -> Self {
+        let text = |name| child_string(node, name).unwrap_or_default();
+        UserInfo {
+            user_id: text("userId"),
+            user_name: text("userName"),
+            user_full_name: text("userFullName"),
+            user_email: text("userEmail"),
+            organization_id: text("organizationId"),
+            organization_name: text("organizationName"),
+            organization_multi_currency: child_bool(node, "organizationMultiCurrency"),
+            profile_id: text("profileId"),
+            role_id: optional_id(node, "roleId"),
+            user_language: text("userLanguage"),
+            user_locale: text("userLocale"),
+            user_time_zone: text("userTimeZone"),
+            user_type: text("userType"),
+            currency_symbol: child_string(node, "currencySymbol").filter(|s| !s.is_empty()),
+            session_seconds_valid: child_parse(node, "sessionSecondsValid").unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roxmltree::Document;
+
+    #[test]
+    fn test_merge_result() {
+        let xml = "<result><id>001A</id><mergedRecordIds>001B</mergedRecordIds>\
+                   <success>true</success><updatedRelatedIds>003A</updatedRelatedIds>\
+                   <updatedRelatedIds>003B</updatedRelatedIds></result>";
+        let doc = Document::parse(xml).unwrap();
+        let result = MergeResult::from_xml(doc.root_element());
+        assert!(result.success);
+        assert_eq!(result.merged_record_ids, ["001B"]);
+        assert_eq!(result.updated_related_ids, ["003A", "003B"]);
+    }
+
+    #[test]
+    fn test_lead_convert_result() {
+        let xml = r#"<result xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <accountId>001A</accountId><contactId>003A</contactId><leadId>00QA</leadId>
+            <opportunityId xsi:nil="true"/><success>true</success></result>"#;
+        let doc = Document::parse(xml).unwrap();
+        let result = LeadConvertResult::from_xml(doc.root_element());
+        assert_eq!(result.account_id.as_deref(), Some("001A"));
+        assert_eq!(result.contact_id.as_deref(), Some("003A"));
+        assert_eq!(result.opportunity_id, None);
+    }
+
+    #[test]
+    fn test_process_result() {
+        let xml = "<result><actorIds>005A</actorIds><entityId>006A</entityId>\
+                   <instanceId>04gA</instanceId><instanceStatus>Pending</instanceStatus>\
+                   <newWorkitemIds>04iA</newWorkitemIds><success>true</success></result>";
+        let doc = Document::parse(xml).unwrap();
+        let result = ProcessResult::from_xml(doc.root_element());
+        assert_eq!(result.instance_status.as_deref(), Some("Pending"));
+        assert_eq!(result.new_workitem_ids, ["04iA"]);
+    }
+
+    #[test]
+    fn test_user_info() {
+        let xml = "<result><organizationId>00DA</organizationId>\
+                   <organizationMultiCurrency>false</organizationMultiCurrency>\
+                   <organizationName>Acme</organizationName><profileId>00eA</profileId>\
+                   <roleId/><sessionSecondsValid>7200</sessionSecondsValid>\
+                   <userEmail>admin@acme.test</userEmail><userFullName>Ada Admin</userFullName>\
+                   <userId>005A</userId><userName>admin@acme.test</userName>\
+                   <userTimeZone>Europe/Berlin</userTimeZone><userType>Standard</userType></result>";
+        let doc = Document::parse(xml).unwrap();
+        let info = UserInfo::from_xml(doc.root_element());
+        assert_eq!(info.user_id, "005A");
+        assert_eq!(info.organization_name, "Acme");
+        assert_eq!(info.role_id, None);
+        assert_eq!(info.session_seconds_valid, 7200);
+    }
+}
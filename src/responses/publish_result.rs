@@ -0,0 +1,117 @@
+use crate::errors::Error;
+use crate::responses::error_response::ErrorResponse;
+use serde::{Deserialize, Serialize};
+
+/// Salesforce reports the `EventUuid` of a published event as an `OPERATION_ENQUEUED` "error".
+const OPERATION_ENQUEUED: &str = "OPERATION_ENQUEUED";
+
+/// Result of publishing one platform event.
+///
+/// The replay ID is only assigned once the event is delivered; match delivered events on
+/// `EventUuid` instead.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.platform_events.meta/platform_events/platform_events_publish_api.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishResult {
+    /// Temporary `e00` ID of the publish operation.
+    #[serde(default)]
+    pub id: String,
+    pub success: bool,
+    /// `EventUuid` of the event, sent with every delivery of it.
+    #[serde(default)]
+    pub event_uuid: Option<String>,
+    #[serde(default)]
+    pub errors: Vec<ErrorResponse>,
+}
+
+impl PublishResult {
+    /// Move the `OPERATION_ENQUEUED` entry out of `errors` into `event_uuid`.
+    pub(crate) fn take_event_uuid(mut self) -> Self {
+        if let Some(pos) = self
+            .errors
+            .iter()
+            .position(|e| e.error_code == OPERATION_ENQUEUED)
+        {
+            self.event_uuid = Some(self.errors.remove(pos).message);
+        }
+        self
+    }
+
+    /// The result, or its errors as `Error::PublishError`.
+    pub fn into_result(self) -> Result<PublishResult, Error> {
+        if self.success {
+            Ok(self)
+        } else {
+            Err(Error::PublishError(self.errors))
+        }
+    }
+}
+
+/// Platform event failures use `PLATFORM_EVENT_*` codes, e.g. `PLATFORM_EVENT_PUBLISH_FAILED`.
+pub(crate) fn publish_error(errors: Vec<ErrorResponse>) -> Error {
+    if errors
+        .iter()
+        .any(|e| e.error_code.starts_with("PLATFORM_EVENT_"))
+    {
+        Error::PublishError(errors)
+    } else {
+        Error::ErrorResponses(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_take_event_uuid() {
+        let result: PublishResult = serde_json::from_value(json!({
+            "id": "e00xx0000000001AAA",
+            "success": true,
+            "errors": [{
+                "statusCode": "OPERATION_ENQUEUED",
+                "message": "08fb2a4e-5b1c-4a0e-9a39-7c5bbd0f1f3c",
+                "fields": []
+            }]
+        }))
+        .unwrap();
+        let result = result.take_event_uuid();
+        assert_eq!(
+            result.event_uuid.as_deref(),
+            Some("08fb2a4e-5b1c-4a0e-9a39-7c5bbd0f1f3c")
+        );
+        assert!(result.errors.is_empty());
+        assert!(result.into_result().is_ok());
+    }
+
+    #[test]
+    fn test_failed_result() {
+        let result: PublishResult = serde_json::from_value(json!({
+            "success": false,
+            "errors": [{"statusCode": "PLATFORM_EVENT_PUBLISH_FAILED", "message": "boom"}]
+        }))
+        .unwrap();
+        let result = result.take_event_uuid();
+        assert_eq!(result.event_uuid, None);
+        assert!(matches!(result.into_result(), Err(Error::PublishError(_))));
+    }
+
+    #[test]
+    fn test_publish_error() {
+        let error = |code: &str| ErrorResponse {
+            message: "failed".to_string(),
+            error_code: code.to_string(),
+            fields: None,
+        };
+        assert!(matches!(
+            publish_error(vec![error("PLATFORM_EVENT_PUBLISHING_UNAVAILABLE")]),
+            Error::PublishError(_)
+        ));
+        assert!(matches!(
+            publish_error(vec![error("INVALID_FIELD")]),
+            Error::ErrorResponses(_)
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// One page of SOQL results.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_query.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult<T> {
+    pub total_size: u64,
+    pub done: bool,
+    /// Relative URL of the next page; pass it to `query_more` while `done` is `false`.
+    #[serde(default)]
+    pub next_records_url: Option<String>,
+    pub records: Vec<T>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_deserialize() {
+        let page: QueryResult<Value> = serde_json::from_value(json!({
+            "totalSize": 3,
+            "done": false,
+            "nextRecordsUrl": "/services/data/v60.0/query/01gxx-2",
+            "records": [{"Id": "001a"}, {"Id": "001b"}]
+        }))
+        .unwrap();
+        assert_eq!(page.total_size, 3);
+        assert!(!page.done);
+        assert_eq!(
+            page.next_records_url.as_deref(),
+            Some("/services/data/v60.0/query/01gxx-2")
+        );
+        assert_eq!(page.records.len(), 2);
+    }
+
+    #[test]
+    fn test_deserialize_last_page() {
+        let page: QueryResult<Value> =
+            serde_json::from_value(json!({"totalSize": 0, "done": true, "records": []})).unwrap();
+        assert!(page.done);
+        assert!(page.next_records_url.is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::datetime::salesforce_datetime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_getupdated.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUpdatedResult {
+    pub ids: Vec<String>,
+    /// Changes up to this point are included; use it as the start of the next request.
+    #[serde(with = "salesforce_datetime")]
+    pub latest_date_covered: DateTime<Utc>,
+}
+
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_getdeleted.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDeletedResult {
+    pub deleted_records: Vec<DeletedRecord>,
+    #[serde(with = "salesforce_datetime")]
+    pub earliest_date_available: DateTime<Utc>,
+    #[serde(with = "salesforce_datetime")]
+    pub latest_date_covered: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedRecord {
+    pub id: String,
+    #[serde(with = "salesforce_datetime")]
+    pub deleted_date: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_updated() {
+        let json_str = json!({
+            "ids": ["001xx000003DGb1", "001xx000003DGb2"],
+            "latestDateCovered": "2013-05-08T21:20:00.000+0000"
+        })
+        .to_string();
+
+        let resp: GetUpdatedResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.ids.len(), 2);
+        assert_eq!(
+            resp.latest_date_covered,
+            Utc.with_ymd_and_hms(2013, 5, 8, 21, 20, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_deserialize_deleted() {
+        let json_str = json!({
+            "deletedRecords": [
+                {"id": "001xx000003DGb3", "deletedDate": "2013-05-07T22:07:19.000+0000"}
+            ],
+            "earliestDateAvailable": "2013-04-08T00:00:00.000+0000",
+            "latestDateCovered": "2013-05-08T21:20:00.000+0000"
+        })
+        .to_string();
+
+        let resp: GetDeletedResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.deleted_records[0].id, "001xx000003DGb3");
+        assert_eq!(
+            resp.deleted_records[0].deleted_date,
+            Utc.with_ymd_and_hms(2013, 5, 7, 22, 7, 19).unwrap()
+        );
+        assert_eq!(
+            resp.earliest_date_available,
+            Utc.with_ymd_and_hms(2013, 4, 8, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let record = DeletedRecord {
+            id: "001xx".to_string(),
+            deleted_date: Utc.with_ymd_and_hms(2013, 5, 7, 22, 7, 19).unwrap(),
+        };
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["deletedDate"], "2013-05-07T22:07:19.000+0000");
+        let back: DeletedRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(back, record);
+    }
+}
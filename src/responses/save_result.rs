@@ -0,0 +1,103 @@
+use crate::responses::error_response::ErrorResponse;
+use crate::xml::{child_bool, child_string, children_named};
+use roxmltree::Node;
+use serde::{Deserialize, Serialize};
+
+/// Result of a single record create or upsert.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_rest.meta/api_rest/resources_sobject_basic_info_post.htm>
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveResult {
+    #[serde(default)]
+    pub id: String,
+    pub success: bool,
+    #[serde(default)]
+    pub errors: Vec<ErrorResponse>,
+    /// `true` when the record was inserted, `false` when an upsert matched an existing record.
+    #[serde(default)]
+    pub created: bool,
+}
+
+impl SaveResult {
+    /// A SOAP result with `id`, `success` and `errors`, e.g. from `undelete`.
+    pub(crate) fn from_xml(node: Node) -> Self {
+        SaveResult {
+            id: child_string(node, "id").unwrap_or_default(),
+            success: child_bool(node, "success"),
+            errors: children_named(node, "errors")
+                .map(ErrorResponse::from_xml)
+                .collect(),
+            created: child_bool(node, "created"),
+        }
+    }
+}
+
+pub type CreateResponse = SaveResult;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_create() {
+        let json_str = json!({
+            "id": "001xx000003DGbX",
+            "success": true,
+            "errors": []
+        })
+        .to_string();
+
+        let resp: SaveResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.id, "001xx000003DGbX");
+        assert!(resp.success);
+        assert!(resp.errors.is_empty());
+        assert!(!resp.created);
+    }
+
+    #[test]
+    fn test_deserialize_upsert() {
+        let json_str = json!({
+            "id": "001xx000003DGbX",
+            "success": true,
+            "errors": [],
+            "created": false
+        })
+        .to_string();
+
+        let resp: SaveResult = serde_json::from_str(&json_str).unwrap();
+        assert!(!resp.created);
+    }
+
+    #[test]
+    fn test_deserialize_with_errors() {
+        let json_str = json!({
+            "success": false,
+            "errors": [{
+                "statusCode": "DUPLICATE_VALUE",
+                "message": "duplicate value found",
+                "fields": ["Name"]
+            }]
+        })
+        .to_string();
+
+        let resp: SaveResult = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(resp.id, "");
+        assert!(!resp.success);
+        assert_eq!(resp.errors[0].error_code, "DUPLICATE_VALUE");
+    }
+
+    #[test]
+    fn test_from_xml() {
+        let xml = "<result><errors><message>entity is not in the recycle bin</message>\
+                   <statusCode>UNDELETE_FAILED</statusCode></errors>\
+                   <id>001xx</id><success>false</success></result>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let result = SaveResult::from_xml(doc.root_element());
+        assert_eq!(result.id, "001xx");
+        assert!(!result.success);
+        assert_eq!(result.errors[0].error_code, "UNDELETE_FAILED");
+        assert_eq!(result.errors[0].fields, None);
+    }
+}
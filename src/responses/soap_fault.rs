@@ -0,0 +1,99 @@
+use crate::xml::child_string;
+use roxmltree::Document;
+
+/// A SOAP fault returned by the Partner or Metadata API.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api.meta/api/sforce_api_calls_concepts_core_data_objects.htm#faults>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoapFault {
+    /// e.g. `sf:INVALID_SESSION_ID`
+    pub fault_code: String,
+    pub fault_string: String,
+    /// Fault type from the detail element, e.g. `InvalidIdFault`.
+    pub fault_type: Option<String>,
+    /// e.g. `INVALID_ID_FIELD`
+    pub exception_code: Option<String>,
+    pub exception_message: Option<String>,
+}
+
+impl SoapFault {
+    /// The fault in a SOAP response body, if it holds one.
+    pub(crate) fn parse(body: &str) -> Option<SoapFault> {
+        let doc = Document::parse(body).ok()?;
+        let fault = doc.descendants().find(|n| n.has_tag_name("Fault"))?;
+        let detail = fault
+            .children()
+            .find(|n| n.has_tag_name("detail"))
+            .and_then(|d| d.first_element_child());
+        Some(SoapFault {
+            fault_code: child_string(fault, "faultcode").unwrap_or_default(),
+            fault_string: child_string(fault, "faultstring").unwrap_or_default(),
+            fault_type: detail.map(|d| d.tag_name().name().to_string()),
+            exception_code: detail.and_then(|d| child_string(d, "exceptionCode")),
+            exception_message: detail.and_then(|d| child_string(d, "exceptionMessage")),
+        })
+    }
+
+    /// The exception code, or the fault code without its namespace prefix.
+    pub fn code(&self) -> &str {
+        match &self.exception_code {
+            Some(code) => code,
+            None => self
+                .fault_code
+                .rsplit_once(':')
+                .map(|(_, code)| code)
+                .unwrap_or(&self.fault_code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_detail() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns:sf="urn:fault.partner.soap.sforce.com">
+                <soapenv:Body>
+                    <soapenv:Fault>
+                        <faultcode>sf:INVALID_ID_FIELD</faultcode>
+                        <faultstring>INVALID_ID_FIELD: Invalid id: 00Qxx</faultstring>
+                        <detail>
+                            <sf:InvalidIdFault>
+                                <sf:exceptionCode>INVALID_ID_FIELD</sf:exceptionCode>
+                                <sf:exceptionMessage>Invalid id: 00Qxx</sf:exceptionMessage>
+                            </sf:InvalidIdFault>
+                        </detail>
+                    </soapenv:Fault>
+                </soapenv:Body>
+            </soapenv:Envelope>"#;
+        let fault = SoapFault::parse(body).unwrap();
+        assert_eq!(fault.fault_code, "sf:INVALID_ID_FIELD");
+        assert_eq!(fault.fault_type.as_deref(), Some("InvalidIdFault"));
+        assert_eq!(
+            fault.exception_message.as_deref(),
+            Some("Invalid id: 00Qxx")
+        );
+        assert_eq!(fault.code(), "INVALID_ID_FIELD");
+    }
+
+    #[test]
+    fn test_parse_without_detail() {
+        let body = r#"<soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/">
+                <soapenv:Body><soapenv:Fault>
+                    <faultcode>sf:INVALID_SESSION_ID</faultcode>
+                    <faultstring>Invalid Session ID found in SessionHeader</faultstring>
+                </soapenv:Fault></soapenv:Body>
+            </soapenv:Envelope>"#;
+        let fault = SoapFault::parse(body).unwrap();
+        assert_eq!(fault.exception_code, None);
+        assert_eq!(fault.code(), "INVALID_SESSION_ID");
+    }
+
+    #[test]
+    fn test_parse_not_a_fault() {
+        assert!(SoapFault::parse("<Envelope><Body/></Envelope>").is_none());
+        assert!(SoapFault::parse("not xml").is_none());
+    }
+}
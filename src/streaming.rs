@@ -0,0 +1,899 @@
+use crate::client::client::Client;
+use crate::datetime::parse_datetime;
+use crate::errors::Error;
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, Stream};
+use reqwest::header::SET_COOKIE;
+use reqwest::{Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const HANDSHAKE: &str = "/meta/handshake";
+const CONNECT: &str = "/meta/connect";
+const SUBSCRIBE: &str = "/meta/subscribe";
+const UNSUBSCRIBE: &str = "/meta/unsubscribe";
+const DISCONNECT: &str = "/meta/disconnect";
+
+/// Where a subscription starts within the event retention window.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_streaming.meta/api_streaming/using_streaming_api_durability.htm>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFrom {
+    /// Only events published after subscribing (`-1`).
+    Tip,
+    /// Every event still retained (`-2`).
+    Earliest,
+    /// Events after this replay ID.
+    ReplayId(i64),
+}
+
+impl ReplayFrom {
+    pub fn value(&self) -> i64 {
+        match self {
+            ReplayFrom::Tip => -1,
+            ReplayFrom::Earliest => -2,
+            ReplayFrom::ReplayId(id) => *id,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct Advice {
+    #[serde(default)]
+    reconnect: Option<String>,
+}
+
+/// A message received from the CometD server.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct BayeuxMessage {
+    channel: String,
+    #[serde(default)]
+    client_id: Option<String>,
+    #[serde(default)]
+    successful: Option<bool>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    subscription: Option<String>,
+    #[serde(default)]
+    advice: Option<Advice>,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+impl BayeuxMessage {
+    fn is_successful(&self) -> bool {
+        self.successful.unwrap_or(false)
+    }
+
+    fn error_message(&self) -> String {
+        self.error
+            .clone()
+            .unwrap_or_else(|| format!("{} was not successful", self.channel))
+    }
+
+    /// The server forgot the client, e.g. `403::Unknown client` or an expired session.
+    fn needs_handshake(&self) -> bool {
+        let advice = self.advice.as_ref().and_then(|a| a.reconnect.as_deref());
+        advice == Some("handshake")
+            || self
+                .error
+                .as_deref()
+                .map_or(false, |e| e.starts_with("403::Unknown client"))
+    }
+}
+
+/// An event delivered on a PushTopic, generic, platform event or change event channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEvent<T = Value> {
+    pub channel: String,
+    pub replay_id: Option<i64>,
+    /// Set on PushTopic and generic events.
+    pub created_date: Option<DateTime<Utc>>,
+    /// PushTopic event type: `created`, `updated`, `deleted` or `undeleted`.
+    pub event_type: Option<String>,
+    /// Schema ID of platform and change events.
+    pub schema: Option<String>,
+    /// The `payload` of platform, change and generic events, or the `sobject` of PushTopic events.
+    pub payload: T,
+}
+
+impl StreamEvent<Value> {
+    fn from_data(channel: String, mut data: Value) -> Self {
+        let event = data.get("event");
+        let replay_id = event.and_then(|e| e["replayId"].as_i64());
+        let created_date = event
+            .and_then(|e| e["createdDate"].as_str())
+            .and_then(|d| parse_datetime(d).ok());
+        let event_type = event
+            .and_then(|e| e["type"].as_str())
+            .map(|t| t.to_string());
+        let schema = data["schema"].as_str().map(|s| s.to_string());
+        let payload = match data.get_mut("payload") {
+            Some(payload) => payload.take(),
+            None => data.get_mut("sobject").map(Value::take).unwrap_or(data),
+        };
+        StreamEvent {
+            channel,
+            replay_id,
+            created_date,
+            event_type,
+            schema,
+            payload,
+        }
+    }
+
+    /// Deserialize the payload into `T`.
+    pub fn decode<T: DeserializeOwned>(self) -> Result<StreamEvent<T>, Error> {
+        Ok(StreamEvent {
+            payload: serde_json::from_value(self.payload)?,
+            channel: self.channel,
+            replay_id: self.replay_id,
+            created_date: self.created_date,
+            event_type: self.event_type,
+            schema: self.schema,
+        })
+    }
+}
+
+/// Bayeux long-polling client for the Streaming API at `/cometd/{version}`.
+///
+/// Subscriptions survive re-handshakes and resume after the last replay ID received.
+///
+/// <https://developer.salesforce.com/docs/atlas.en-us.api_streaming.meta/api_streaming/intro_stream.htm>
+pub struct StreamingClient {
+    pub(crate) client: Client,
+    client_id: Option<String>,
+    /// Channel and the replay ID to resume it from.
+    subscriptions: Vec<(String, i64)>,
+    /// The server pins a client to an app server with cookies such as `BAYEUX_BROWSER`.
+    cookies: Vec<(String, String)>,
+    retry_delay: Duration,
+}
+
+impl StreamingClient {
+    pub fn new(client: Client) -> Self {
+        StreamingClient {
+            client,
+            client_id: None,
+            subscriptions: vec![],
+            cookies: vec![],
+            retry_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// How long `into_stream` waits before connecting again after an error. Defaults to 5 seconds.
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// The Bayeux client ID, set after a successful handshake.
+    pub fn client_id(&self) -> Option<&str> {
+        self.client_id.as_deref()
+    }
+
+    /// Replay ID of the last event received on `channel`, or the one it was subscribed from.
+    pub fn replay_id(&self, channel: &str) -> Option<i64> {
+        self.subscriptions
+            .iter()
+            .find(|(c, _)| c == channel)
+            .map(|(_, id)| *id)
+    }
+
+    /// `{instance_url}/cometd/{version}`, with the version number lacking the `v` prefix.
+    pub(crate) fn cometd_url(&self) -> Result<String, Error> {
+        let instance_url = self
+            .client
+            .instance_url
+            .as_ref()
+            .ok_or(Error::NotLoggedIn)?;
+        Ok(format!(
+            "{}/cometd/{}",
+            instance_url,
+            self.client.version.trim_start_matches('v')
+        ))
+    }
+
+    pub async fn handshake(&mut self) -> Result<(), Error> {
+        self.client_id = None;
+        let message = json!([{
+            "channel": HANDSHAKE,
+            "version": "1.0",
+            "minimumVersion": "1.0",
+            "supportedConnectionTypes": ["long-polling"],
+            "ext": { "replay": true },
+        }]);
+        let replies = self.send(message).await?;
+        let reply = reply_on(&replies, HANDSHAKE)?;
+        match (&reply.client_id, reply.is_successful()) {
+            (Some(client_id), true) => {
+                self.client_id = Some(client_id.clone());
+                Ok(())
+            }
+            _ => Err(Error::StreamingError(reply.error_message())),
+        }
+    }
+
+    /// Subscribe to a channel such as `/topic/InvoiceUpdates`, `/event/Order_Shipped__e` or
+    /// `/data/AccountChangeEvent`, handshaking first when needed.
+    pub async fn subscribe(&mut self, channel: &str, replay: ReplayFrom) -> Result<(), Error> {
+        self.subscriptions.retain(|(c, _)| c != channel);
+        if self.client_id.is_none() {
+            self.resubscribe().await?;
+        }
+
+        self.subscriptions
+            .push((channel.to_string(), replay.value()));
+        let replies = self.send(self.subscribe_messages(&[channel])).await?;
+        let reply = reply_on(&replies, SUBSCRIBE)?;
+        if reply.is_successful() {
+            Ok(())
+        } else if reply.needs_handshake() {
+            self.resubscribe().await
+        } else {
+            self.subscriptions.retain(|(c, _)| c != channel);
+            Err(Error::StreamingError(reply.error_message()))
+        }
+    }
+
+    pub async fn unsubscribe(&mut self, channel: &str) -> Result<(), Error> {
+        self.subscriptions.retain(|(c, _)| c != channel);
+        let client_id = match &self.client_id {
+            Some(client_id) => client_id.clone(),
+            None => return Ok(()),
+        };
+        let message = json!([{
+            "channel": UNSUBSCRIBE,
+            "clientId": client_id,
+            "subscription": channel,
+        }]);
+        let replies = self.send(message).await?;
+        let reply = reply_on(&replies, UNSUBSCRIBE)?;
+        if reply.is_successful() || reply.needs_handshake() {
+            Ok(())
+        } else {
+            Err(Error::StreamingError(reply.error_message()))
+        }
+    }
+
+    /// Long-poll for events. Re-handshakes and resubscribes when the server has forgotten
+    /// the client, and refreshes the access token when the session expired.
+    pub async fn connect(&mut self) -> Result<Vec<StreamEvent>, Error> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            if self.client_id.is_none() {
+                self.resubscribe().await?;
+            }
+            let message = json!([{
+                "channel": CONNECT,
+                "clientId": self.client_id,
+                "connectionType": "long-polling",
+            }]);
+            let replies = self.send(message).await?;
+
+            let mut events = vec![];
+            let mut failure = None;
+            for reply in replies {
+                if reply.channel == CONNECT {
+                    if !reply.is_successful() {
+                        failure = Some(reply);
+                    }
+                } else if let Some(data) = reply.data {
+                    let event = StreamEvent::from_data(reply.channel, data);
+                    self.record_replay_id(&event);
+                    events.push(event);
+                }
+            }
+
+            match failure {
+                Some(reply) if reply.needs_handshake() => {
+                    self.client_id = None;
+                    if !events.is_empty() || attempts > 1 {
+                        return Ok(events);
+                    }
+                }
+                Some(reply) => return Err(Error::StreamingError(reply.error_message())),
+                None => return Ok(events),
+            }
+        }
+    }
+
+    pub async fn disconnect(&mut self) -> Result<(), Error> {
+        if let Some(client_id) = self.client_id.take() {
+            let message = json!([{ "channel": DISCONNECT, "clientId": client_id }]);
+            self.send(message).await?;
+        }
+        Ok(())
+    }
+
+    /// Connect in a loop and yield each event with its payload decoded as `T`. The stream
+    /// never ends; errors are yielded and connecting carries on after the retry delay.
+    pub fn into_stream<T: DeserializeOwned>(
+        self,
+    ) -> impl Stream<Item = Result<StreamEvent<T>, Error>> {
+        let state = (self, VecDeque::<StreamEvent>::new(), false);
+        stream::unfold(state, |(mut client, mut pending, mut failed)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((event.decode(), (client, pending, failed)));
+                }
+                if failed {
+                    tokio::time::sleep(client.retry_delay).await;
+                }
+                match client.connect().await {
+                    Ok(events) => {
+                        failed = false;
+                        pending.extend(events);
+                    }
+                    Err(e) => return Some((Err(e), (client, pending, true))),
+                }
+            }
+        })
+    }
+
+    /// Handshake, then subscribe to every channel again from its last replay ID.
+    async fn resubscribe(&mut self) -> Result<(), Error> {
+        self.handshake().await?;
+        if self.subscriptions.is_empty() {
+            return Ok(());
+        }
+        let channels: Vec<String> = self.subscriptions.iter().map(|(c, _)| c.clone()).collect();
+        let channels: Vec<&str> = channels.iter().map(|c| c.as_str()).collect();
+        let replies = self.send(self.subscribe_messages(&channels)).await?;
+        match replies
+            .iter()
+            .find(|r| r.channel == SUBSCRIBE && !r.is_successful())
+        {
+            Some(reply) => Err(Error::StreamingError(format!(
+                "{}: {}",
+                reply.subscription.as_deref().unwrap_or_default(),
+                reply.error_message()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn subscribe_messages(&self, channels: &[&str]) -> Value {
+        let messages: Vec<Value> = channels
+            .iter()
+            .map(|channel| {
+                let replay_id = self.replay_id(channel).unwrap_or(-1);
+                json!({
+                    "channel": SUBSCRIBE,
+                    "clientId": self.client_id,
+                    "subscription": channel,
+                    "ext": { "replay": { *channel: replay_id } },
+                })
+            })
+            .collect();
+        Value::Array(messages)
+    }
+
+    fn record_replay_id(&mut self, event: &StreamEvent) {
+        if let Some(replay_id) = event.replay_id {
+            if let Some(subscription) = self
+                .subscriptions
+                .iter_mut()
+                .find(|(c, _)| *c == event.channel)
+            {
+                subscription.1 = replay_id;
+            }
+        }
+    }
+
+    async fn send(&mut self, messages: Value) -> Result<Vec<BayeuxMessage>, Error> {
+        let url = self.cometd_url()?;
+        let mut response = self
+            .client
+            .post(url.clone(), &messages, self.cookie_header())
+            .await?;
+        self.store_cookies(&response);
+        if response.status() == StatusCode::UNAUTHORIZED {
+            // The session expired; retry once with a fresh token
+            log::info!("Streaming API session expired, refreshing.");
+            self.client.refresh().await?;
+            response = self
+                .client
+                .post(url, &messages, self.cookie_header())
+                .await?;
+            self.store_cookies(&response);
+        }
+
+        let status = response.status();
+        let body = response.text().await?;
+        match serde_json::from_str::<Vec<BayeuxMessage>>(&body) {
+            Ok(replies) => Ok(replies),
+            Err(_) if !status.is_success() => {
+                Err(Error::StreamingError(format!("{}: {}", status, body)))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn cookie_header(&self) -> Vec<(String, String)> {
+        if self.cookies.is_empty() {
+            return vec![];
+        }
+        let cookies: Vec<String> = self
+            .cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        vec![("Cookie".to_string(), cookies.join("; "))]
+    }
+
+    fn store_cookies(&mut self, response: &Response) {
+        for header in response.headers().get_all(SET_COOKIE) {
+            let cookie = header.to_str().unwrap_or_default();
+            let pair = cookie.split(';').next().unwrap_or_default();
+            if let Some((name, value)) = pair.split_once('=') {
+                let name = name.trim().to_string();
+                let value = value.trim().to_string();
+                match self.cookies.iter_mut().find(|(n, _)| *n == name) {
+                    Some(cookie) => cookie.1 = value,
+                    None => self.cookies.push((name, value)),
+                }
+            }
+        }
+    }
+}
+
+fn reply_on<'a>(replies: &'a [BayeuxMessage], channel: &str) -> Result<&'a BayeuxMessage, Error> {
+    replies
+        .iter()
+        .find(|r| r.channel == channel)
+        .ok_or_else(|| Error::StreamingError(format!("no reply on {}", channel)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use futures_util::StreamExt;
+    use mockito::{Matcher, Mock, Server, ServerGuard};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn create_test_streaming_client(server_url: &str) -> StreamingClient {
+        let mut client = Client::new();
+        client.set_instance_url(server_url);
+        client.set_access_token(
+            "test_token".to_string(),
+            "9999999999000".to_string(),
+            "Bearer".to_string(),
+        );
+        client.set_version("v60.0");
+        StreamingClient::new(client).with_retry_delay(Duration::from_millis(1))
+    }
+
+    fn channel(name: &str) -> Matcher {
+        Matcher::Regex(format!(r#""channel":"{}""#, name))
+    }
+
+    /// Hands out `client-1`, `client-2`, ... on each handshake and sets a session cookie.
+    async fn mock_handshake(server: &mut ServerGuard) -> Mock {
+        let handshakes = Arc::new(AtomicUsize::new(0));
+        server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(HANDSHAKE))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("set-cookie", "BAYEUX_BROWSER=b1; Path=/; Secure")
+            .with_body_from_request(move |_| {
+                let n = handshakes.fetch_add(1, Ordering::SeqCst) + 1;
+                json!([{
+                    "channel": HANDSHAKE,
+                    "clientId": format!("client-{}", n),
+                    "successful": true,
+                    "version": "1.0",
+                    "supportedConnectionTypes": ["long-polling"]
+                }])
+                .to_string()
+                .into()
+            })
+            .create_async()
+            .await
+    }
+
+    /// Accepts every subscription in the request.
+    async fn mock_subscribe(server: &mut ServerGuard) -> Mock {
+        server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(SUBSCRIBE))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_request(|request| {
+                let messages: Vec<Value> = serde_json::from_slice(request.body().unwrap()).unwrap();
+                let replies: Vec<Value> = messages
+                    .iter()
+                    .map(|m| {
+                        json!({
+                            "channel": SUBSCRIBE,
+                            "clientId": m["clientId"],
+                            "subscription": m["subscription"],
+                            "successful": true
+                        })
+                    })
+                    .collect();
+                Value::Array(replies).to_string().into()
+            })
+            .create_async()
+            .await
+    }
+
+    fn connect_reply(events: Vec<Value>) -> String {
+        let mut replies = events;
+        replies.push(json!({"channel": CONNECT, "successful": true}));
+        Value::Array(replies).to_string()
+    }
+
+    fn push_topic_event(replay_id: i64) -> Value {
+        json!({
+            "channel": "/topic/InvoiceUpdates",
+            "data": {
+                "event": {
+                    "createdDate": "2024-01-01T12:00:00.000Z",
+                    "replayId": replay_id,
+                    "type": "created"
+                },
+                "sobject": {"Id": "a00xx", "Name": "INV-1"}
+            }
+        })
+    }
+
+    #[test]
+    fn test_cometd_url() {
+        let client = create_test_streaming_client("https://example.my.salesforce.com");
+        assert_eq!(
+            client.cometd_url().unwrap(),
+            "https://example.my.salesforce.com/cometd/60.0"
+        );
+        assert!(matches!(
+            StreamingClient::new(Client::new()).cometd_url(),
+            Err(Error::NotLoggedIn)
+        ));
+    }
+
+    #[test]
+    fn test_replay_from() {
+        assert_eq!(ReplayFrom::Tip.value(), -1);
+        assert_eq!(ReplayFrom::Earliest.value(), -2);
+        assert_eq!(ReplayFrom::ReplayId(42).value(), 42);
+    }
+
+    #[test]
+    fn test_stream_event_from_data() {
+        let event = StreamEvent::from_data(
+            "/topic/InvoiceUpdates".to_string(),
+            push_topic_event(5)["data"].clone(),
+        );
+        assert_eq!(event.replay_id, Some(5));
+        assert_eq!(
+            event.created_date,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap())
+        );
+        assert_eq!(event.event_type.as_deref(), Some("created"));
+        assert_eq!(event.payload["Name"], "INV-1");
+
+        let event = StreamEvent::from_data(
+            "/event/Order_Shipped__e".to_string(),
+            json!({
+                "schema": "dffQ2QLzDNHqwB8_sHMxdA",
+                "payload": {"Order_Number__c": "A-1"},
+                "event": {"replayId": 7}
+            }),
+        );
+        assert_eq!(event.schema.as_deref(), Some("dffQ2QLzDNHqwB8_sHMxdA"));
+        assert_eq!(event.payload["Order_Number__c"], "A-1");
+        assert_eq!(event.created_date, None);
+    }
+
+    #[test]
+    fn test_decode_change_event() {
+        use crate::change_data_capture::{ChangeEvent, ChangeType};
+
+        let event = StreamEvent::from_data(
+            "/data/AccountChangeEvent".to_string(),
+            json!({
+                "schema": "IeRuaY6cbI_HsV8Rv1Mc5g",
+                "payload": {
+                    "ChangeEventHeader": {
+                        "entityName": "Account",
+                        "recordIds": ["001xx"],
+                        "changeType": "UPDATE",
+                        "transactionKey": "0002343d",
+                        "sequenceNumber": 1,
+                        "commitTimestamp": 1704110400000i64,
+                        "changedFields": ["Name"]
+                    },
+                    "Name": "Acme"
+                },
+                "event": {"replayId": 12}
+            }),
+        );
+        let event = event.decode::<ChangeEvent>().unwrap();
+        assert_eq!(event.replay_id, Some(12));
+        assert_eq!(event.payload.header.change_type, ChangeType::Update);
+        assert_eq!(event.payload.fields["Name"], "Acme");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_connect() {
+        let mut server = Server::new_async().await;
+        let handshake = mock_handshake(&mut server).await;
+        let subscribe = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(Matcher::AllOf(vec![
+                channel(SUBSCRIBE),
+                Matcher::Regex(r#""replay":\{"/topic/InvoiceUpdates":-2\}"#.into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!([{
+                    "channel": SUBSCRIBE,
+                    "clientId": "client-1",
+                    "subscription": "/topic/InvoiceUpdates",
+                    "successful": true
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let connect = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(CONNECT))
+            .match_header("cookie", "BAYEUX_BROWSER=b1")
+            .match_header("authorization", "Bearer test_token")
+            .with_status(200)
+            .with_body(connect_reply(vec![push_topic_event(5)]))
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        client
+            .subscribe("/topic/InvoiceUpdates", ReplayFrom::Earliest)
+            .await
+            .unwrap();
+        assert_eq!(client.client_id(), Some("client-1"));
+        assert_eq!(client.replay_id("/topic/InvoiceUpdates"), Some(-2));
+
+        let events = client.connect().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].payload["Id"], "a00xx");
+        assert_eq!(client.replay_id("/topic/InvoiceUpdates"), Some(5));
+        handshake.assert_async().await;
+        subscribe.assert_async().await;
+        connect.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rehandshake_on_unknown_client() {
+        let mut server = Server::new_async().await;
+        let handshake = mock_handshake(&mut server).await.expect(2);
+        let subscribe = mock_subscribe(&mut server).await;
+        let forgotten = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(Matcher::AllOf(vec![
+                channel(CONNECT),
+                Matcher::Regex(r#""clientId":"client-1""#.into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!([{
+                    "channel": CONNECT,
+                    "successful": false,
+                    "error": "403::Unknown client",
+                    "advice": {"reconnect": "handshake", "interval": 0}
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let resubscribed = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(Matcher::AllOf(vec![
+                channel(SUBSCRIBE),
+                Matcher::Regex(r#""replay":\{"/topic/InvoiceUpdates":10\}"#.into()),
+                Matcher::Regex(r#""clientId":"client-2""#.into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                json!([{"channel": SUBSCRIBE, "subscription": "/topic/InvoiceUpdates", "successful": true}])
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        let connect = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(Matcher::AllOf(vec![
+                channel(CONNECT),
+                Matcher::Regex(r#""clientId":"client-2""#.into()),
+            ]))
+            .with_status(200)
+            .with_body(connect_reply(vec![push_topic_event(11)]))
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        client
+            .subscribe("/topic/InvoiceUpdates", ReplayFrom::ReplayId(10))
+            .await
+            .unwrap();
+        let events = client.connect().await.unwrap();
+        assert_eq!(events[0].replay_id, Some(11));
+        assert_eq!(client.client_id(), Some("client-2"));
+        handshake.assert_async().await;
+        subscribe.assert_async().await;
+        forgotten.assert_async().await;
+        resubscribed.assert_async().await;
+        connect.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_refresh_on_expired_session() {
+        let mut server = Server::new_async().await;
+        let token = server
+            .mock("POST", "/services/oauth2/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "access_token": "refreshed_token",
+                    "issued_at": "9999999999000",
+                    "id": "https://login.salesforce.com/id/00Dxx/005xx",
+                    "instance_url": server.url(),
+                    "signature": "sig",
+                    "token_type": "Bearer",
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        mock_handshake(&mut server).await;
+        mock_subscribe(&mut server).await;
+        let expired = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(CONNECT))
+            .match_header("authorization", "Bearer test_token")
+            .with_status(401)
+            .with_body(
+                json!([{
+                    "channel": CONNECT,
+                    "error": "401::Authentication invalid",
+                    "advice": {"reconnect": "handshake"},
+                    "successful": false
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let connect = server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(CONNECT))
+            .match_header("authorization", "Bearer refreshed_token")
+            .with_status(200)
+            .with_body(connect_reply(vec![push_topic_event(3)]))
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        client.client.set_login_endpoint(&server.url());
+        client.client.set_refresh_token("rtoken");
+        client
+            .subscribe("/topic/InvoiceUpdates", ReplayFrom::Tip)
+            .await
+            .unwrap();
+        let events = client.connect().await.unwrap();
+        assert_eq!(events[0].replay_id, Some(3));
+        token.assert_async().await;
+        expired.assert_async().await;
+        connect.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_handshake_denied() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/cometd/60.0")
+            .with_status(200)
+            .with_body(
+                json!([{
+                    "channel": HANDSHAKE,
+                    "successful": false,
+                    "error": "403::Handshake denied",
+                    "advice": {"reconnect": "none"}
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        match client.subscribe("/topic/Missing", ReplayFrom::Tip).await {
+            Err(Error::StreamingError(msg)) => assert_eq!(msg, "403::Handshake denied"),
+            other => panic!("expected StreamingError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_refused() {
+        let mut server = Server::new_async().await;
+        mock_handshake(&mut server).await;
+        server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(SUBSCRIBE))
+            .with_status(200)
+            .with_body(
+                json!([{
+                    "channel": SUBSCRIBE,
+                    "subscription": "/topic/Missing",
+                    "successful": false,
+                    "error": "400::The channel you requested to subscribe to does not exist"
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        let result = client.subscribe("/topic/Missing", ReplayFrom::Tip).await;
+        assert!(matches!(result, Err(Error::StreamingError(_))));
+        assert_eq!(client.replay_id("/topic/Missing"), None);
+    }
+
+    #[tokio::test]
+    async fn test_into_stream() {
+        #[derive(Deserialize, Debug)]
+        struct OrderShipped {
+            #[serde(rename = "Order_Number__c")]
+            order_number: String,
+        }
+
+        let mut server = Server::new_async().await;
+        mock_handshake(&mut server).await;
+        mock_subscribe(&mut server).await;
+        server
+            .mock("POST", "/cometd/60.0")
+            .match_body(channel(CONNECT))
+            .with_status(200)
+            .with_body(connect_reply(vec![
+                json!({
+                    "channel": "/event/Order_Shipped__e",
+                    "data": {
+                        "schema": "dffQ2QLzDNHqwB8_sHMxdA",
+                        "payload": {"Order_Number__c": "A-1"},
+                        "event": {"replayId": 7}
+                    }
+                }),
+                json!({
+                    "channel": "/event/Order_Shipped__e",
+                    "data": {
+                        "schema": "dffQ2QLzDNHqwB8_sHMxdA",
+                        "payload": {"Order_Number__c": "A-2"},
+                        "event": {"replayId": 8}
+                    }
+                }),
+            ]))
+            .create_async()
+            .await;
+
+        let mut client = create_test_streaming_client(&server.url());
+        client
+            .subscribe("/event/Order_Shipped__e", ReplayFrom::Tip)
+            .await
+            .unwrap();
+        let mut stream = Box::pin(client.into_stream::<OrderShipped>());
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.payload.order_number, "A-1");
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!(second.replay_id, Some(8));
+        assert_eq!(second.payload.order_number, "A-2");
+    }
+}
@@ -1,4 +1,6 @@
-use roxmltree::Document;
+use roxmltree::{Document, Node};
+use serde_json::{Map, Value};
+use std::str::FromStr;
 
 pub(crate) fn extract_xml_tag(tag_name: &str, body: &str) -> Option<String> {
     let doc = Document::parse(body).ok()?;
@@ -6,22 +8,197 @@ pub(crate) fn extract_xml_tag(tag_name: &str, body: &str) -> Option<String> {
     Some(node.text()?.to_string())
 }
 
+/// Escape text for use in XML element content or attribute values.
+pub(crate) fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds XML documents with every text and attribute value escaped.
+#[derive(Debug, Default)]
+pub(crate) struct XmlWriter {
+    buffer: String,
+}
+
+impl XmlWriter {
+    pub fn new() -> Self {
+        XmlWriter::default()
+    }
+
+    pub fn start(&mut self, name: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.buffer.push('<');
+        self.buffer.push_str(name);
+        for (key, value) in attributes {
+            self.buffer
+                .push_str(&format!(" {}=\"{}\"", key, escape_xml(value)));
+        }
+        self.buffer.push('>');
+        self
+    }
+
+    pub fn end(&mut self, name: &str) -> &mut Self {
+        self.buffer.push_str(&format!("</{}>", name));
+        self
+    }
+
+    /// `<name>text</name>`
+    pub fn element(&mut self, name: &str, text: &str) -> &mut Self {
+        self.start(name, &[]);
+        self.buffer.push_str(&escape_xml(text));
+        self.end(name)
+    }
+
+    pub fn empty(&mut self, name: &str) -> &mut Self {
+        self.buffer.push_str(&format!("<{}/>", name));
+        self
+    }
+
+    /// Write a JSON value as elements: objects become child elements, arrays repeat the
+    /// element and `null` is left out.
+    pub fn value(&mut self, name: &str, value: &Value) -> &mut Self {
+        match value {
+            Value::Null => {}
+            Value::Array(items) => {
+                for item in items {
+                    self.value(name, item);
+                }
+            }
+            Value::Object(fields) => {
+                self.start(name, &[]);
+                self.object_fields(fields);
+                self.end(name);
+            }
+            Value::String(text) => {
+                self.element(name, text);
+            }
+            other => {
+                self.element(name, &other.to_string());
+            }
+        }
+        self
+    }
+
+    /// The fields of an object, with `fullName` first as Salesforce lists it.
+    pub fn object_fields(&mut self, fields: &Map<String, Value>) -> &mut Self {
+        if let Some(full_name) = fields.get("fullName") {
+            self.value("fullName", full_name);
+        }
+        for (key, value) in fields.iter().filter(|(key, _)| *key != "fullName") {
+            self.value(key, value);
+        }
+        self
+    }
+
+    pub fn into_string(self) -> String {
+        self.buffer
+    }
+}
+
+/// Text of the first direct child element named `name`.
+pub(crate) fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .map(|n| n.text().unwrap_or_default())
+}
+
+pub(crate) fn child_string(node: Node, name: &str) -> Option<String> {
+    child_text(node, name).map(|t| t.to_string())
+}
+
+/// Parsed value of a direct child element; `None` when missing or unparseable.
+pub(crate) fn child_parse<T: FromStr>(node: Node, name: &str) -> Option<T> {
+    child_text(node, name).and_then(|t| t.trim().parse().ok())
+}
+
+pub(crate) fn child_bool(node: Node, name: &str) -> bool {
+    child_parse(node, name).unwrap_or(false)
+}
+
+pub(crate) fn children_named<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+/// Convert an element into JSON: text-only elements become strings, repeated child
+/// elements become arrays and `xsi:nil` elements become `null`. Attributes are dropped.
+pub(crate) fn node_to_value(node: Node) -> Value {
+    if node
+        .attributes()
+        .any(|a| a.name() == "nil" && a.value() == "true")
+    {
+        return Value::Null;
+    }
+    if !node.children().any(|n| n.is_element()) {
+        return Value::String(node.text().unwrap_or_default().to_string());
+    }
+
+    let mut fields = Map::new();
+    for child in node.children().filter(|n| n.is_element()) {
+        let name = child.tag_name().name().to_string();
+        let value = node_to_value(child);
+        match fields.get_mut(&name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                fields.insert(name, value);
+            }
+        }
+    }
+    Value::Object(fields)
+}
+
 pub(crate) fn create_login_envelope(username: &str, password: &str) -> String {
-    [
-        "<se:Envelope xmlns:se='http://schemas.xmlsoap.org/soap/envelope/'>",
-        "<se:Header/>",
-        "<se:Body>",
-        "<login xmlns='urn:partner.soap.sforce.com'>",
-        &format!("<username>{}</username>", username),
-        &format!("<password>{}</password>", password),
-        "</login>",
-        "</se:Body>",
-        "</se:Envelope>",
-    ]
-    .join("")
+    let mut writer = XmlWriter::new();
+    writer
+        .start(
+            "se:Envelope",
+            &[("xmlns:se", "http://schemas.xmlsoap.org/soap/envelope/")],
+        )
+        .empty("se:Header")
+        .start("se:Body", &[])
+        .start("login", &[("xmlns", "urn:partner.soap.sforce.com")])
+        .element("username", username)
+        .element("password", password)
+        .end("login")
+        .end("se:Body")
+        .end("se:Envelope");
+    writer.into_string()
 }
 
+/// Helpers shared by the tests of the SOAP based APIs.
 #[cfg(test)]
+pub(crate) mod test_support {
+    /// A SOAP envelope answering `operation` with `body` in the given namespace.
+    pub(crate) fn soap_response(namespace: &str, operation: &str, body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <soapenv:Envelope xmlns:soapenv="http://schemas.xmlsoap.org/soap/envelope/" xmlns="{0}">
+                <soapenv:Body>
+                    <{1}Response>{2}</{1}Response>
+                </soapenv:Body>
+            </soapenv:Envelope>"#,
+            namespace, operation, body
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_or_default)]
 mod tests {
     use super::*;
     use html_escape::decode_html_entities;
@@ -29,14 +206,106 @@ mod tests {
     #[test]
     fn test_create_envelope() {
         let envelope = create_login_envelope("u", "p");
-        assert_eq!(envelope, "<se:Envelope xmlns:se='http://schemas.xmlsoap.org/soap/envelope/'><se:Header/><se:Body><login xmlns='urn:partner.soap.sforce.com'><username>u</username><password>p</password></login></se:Body></se:Envelope>")
+        assert_eq!(envelope, "<se:Envelope xmlns:se=\"http://schemas.xmlsoap.org/soap/envelope/\"><se:Header/><se:Body><login xmlns=\"urn:partner.soap.sforce.com\"><username>u</username><password>p</password></login></se:Body></se:Envelope>")
     }
 
     #[test]
     fn test_create_envelope_with_special_chars() {
         let envelope = create_login_envelope("user@test.com", "p@ss&word");
         assert!(envelope.contains("<username>user@test.com</username>"));
-        assert!(envelope.contains("<password>p@ss&word</password>"));
+        assert!(envelope.contains("<password>p@ss&amp;word</password>"));
+    }
+
+    #[test]
+    fn test_create_envelope_escapes_markup() {
+        let envelope = create_login_envelope("u", "</password><x>");
+        assert!(envelope.contains("<password>&lt;/password&gt;&lt;x&gt;</password>"));
+        assert_eq!(
+            extract_xml_tag("password", &envelope),
+            Some("</password><x>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+        );
+        assert_eq!(escape_xml("plain"), "plain");
+    }
+
+    #[test]
+    fn test_xml_writer_escapes() {
+        let mut writer = XmlWriter::new();
+        writer
+            .start("root", &[("xmlns", "urn:a&b")])
+            .element("name", "<Tom & Jerry>")
+            .empty("none")
+            .end("root");
+        assert_eq!(
+            writer.into_string(),
+            "<root xmlns=\"urn:a&amp;b\"><name>&lt;Tom &amp; Jerry&gt;</name><none/></root>"
+        );
+    }
+
+    #[test]
+    fn test_xml_writer_value() {
+        let value = serde_json::json!({
+            "label": "Tier",
+            "fullName": "Account.Tier__c",
+            "length": 20,
+            "required": false,
+            "description": null,
+            "valueSet": {"value": [{"fullName": "Gold"}, {"fullName": "Silver"}]}
+        });
+        let mut writer = XmlWriter::new();
+        writer.value("metadata", &value);
+        assert_eq!(
+            writer.into_string(),
+            "<metadata><fullName>Account.Tier__c</fullName><label>Tier</label>\
+             <length>20</length><required>false</required><valueSet>\
+             <value><fullName>Gold</fullName></value><value><fullName>Silver</fullName></value>\
+             </valueSet></metadata>"
+        );
+    }
+
+    #[test]
+    fn test_node_to_value() {
+        let xml = r#"<records xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="CustomObject">
+            <fullName>Invoice__c</fullName>
+            <description xsi:nil="true"/>
+            <fields><fullName>Amount__c</fullName></fields>
+            <fields><fullName>Due__c</fullName></fields>
+            <label>Invoice</label>
+        </records>"#;
+        let doc = Document::parse(xml).unwrap();
+        assert_eq!(
+            node_to_value(doc.root_element()),
+            serde_json::json!({
+                "fullName": "Invoice__c",
+                "description": null,
+                "fields": [{"fullName": "Amount__c"}, {"fullName": "Due__c"}],
+                "label": "Invoice"
+            })
+        );
+    }
+
+    #[test]
+    fn test_child_helpers() {
+        let xml = "<r><id>09S</id><done>true</done><count>3</count><m>a</m><m>b</m><e/></r>";
+        let doc = Document::parse(xml).unwrap();
+        let root = doc.root_element();
+        assert_eq!(child_text(root, "id"), Some("09S"));
+        assert_eq!(child_string(root, "missing"), None);
+        assert_eq!(child_text(root, "e"), Some(""));
+        assert!(child_bool(root, "done"));
+        assert!(!child_bool(root, "missing"));
+        assert_eq!(child_parse::<u32>(root, "count"), Some(3));
+        let members: Vec<_> = children_named(root, "m")
+            .map(|n| n.text().unwrap())
+            .collect();
+        assert_eq!(members, vec!["a", "b"]);
     }
 
     #[test]